@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use macroquad::prelude::*;
+
+use crate::events::{Cause, Event, Listener};
+use crate::mode::Outcome;
+use crate::stats::KIND_NAMES;
+use crate::storage;
+
+// ---- Achievements: definitions from achievements.txt, progress in the data dir ----
+// Each definition names a metric and a target. The game feeds metrics as things
+// happen; lifetime metrics add up across sessions, best metrics keep the highest
+// value seen in one go:
+//   lifetime  kills, kills.<kind>, bomb_kills.<kind>, powerups, clean_waves, runs, missions
+//   best      wave (waves modes), combo_kills (kills in one combo), score (one run)
+// where <kind> is normal / fast / tank. Progress and unlock dates are saved to
+// <data dir>/achievements_progress.txt (on unlock, at game over and on quit); a
+// toast pops over the HUD on unlock. Metrics come
+// from the gameplay event stream (see the Listener impl at the bottom).
+// -----------------------------------------------------------------------------------
+
+pub const DEFS_FILE: &str = "achievements.txt";
+const SAVE_FILE: &str = "achievements_progress.txt";
+const OLD_SAVE_FILE: &str = "achievements.txt"; // before the rename; shares DEFS_FILE's name, so only read if it is a save
+const SAVE_HEADER: &str = "# zombie_shooter achievements v1";
+const TOAST_TIME: f32 = 4.0;
+const METRICS: [&str; 14] = [
+    "kills", "kills.normal", "kills.fast", "kills.tank", "bomb_kills.normal", "bomb_kills.fast", "bomb_kills.tank",
+    "powerups", "clean_waves", "runs", "missions", "wave", "combo_kills", "score",
+];
+
+pub struct Def { pub id: String, pub metric: String, pub target: u32, pub name: String, pub desc: String }
+
+pub struct Achievements {
+    pub defs: Vec<Def>,
+    progress: HashMap<String, u32>, // by metric
+    unlocked: HashMap<String, String>, // id -> date
+    toasts: Vec<(String, f32)>,
+    clean_wave: bool, // no damage taken since the wave started
+    pub warnings: Vec<String>,
+}
+
+fn parse_defs(src: &str) -> (Vec<Def>, Vec<String>) {
+    let (mut defs, mut errs) = (vec![], vec![]);
+    for (ln, raw) in src.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or("").trim();
+        if line.is_empty() { continue; }
+        let err = |msg: &str| format!("{} line {}: {}", DEFS_FILE, ln + 1, msg);
+        let (head, desc) = line.split_once('|').unwrap_or((line, ""));
+        let mut f = head.split_whitespace();
+        let (Some(id), Some(metric), Some(target)) = (f.next(), f.next(), f.next()) else { errs.push(err("expected <id> <metric> <target> <name> | <description>")); continue; };
+        let Ok(target) = target.parse::<u32>() else { errs.push(err("target must be a whole number")); continue; };
+        if !METRICS.contains(&metric) { errs.push(err(&format!("unknown metric `{}`", metric))); continue; }
+        if defs.iter().any(|d: &Def| d.id == id) { errs.push(err(&format!("duplicate id `{}`", id))); continue; }
+        let name = f.collect::<Vec<_>>().join(" ");
+        defs.push(Def { id: id.to_owned(), metric: metric.to_owned(), target: target.max(1), name: if name.is_empty() { id.to_owned() } else { name }, desc: desc.trim().to_owned() });
+    }
+    (defs, errs)
+}
+
+impl Achievements {
+    pub fn load() -> Achievements {
+        let (defs, mut warnings) = match std::fs::read_to_string(DEFS_FILE) {
+            Ok(src) => parse_defs(&src),
+            Err(e) => (vec![], vec![format!("couldn't read {}: {}", DEFS_FILE, e)]),
+        };
+        let (mut progress, mut unlocked) = (HashMap::new(), HashMap::new());
+        let text = std::fs::read_to_string(Achievements::save_path())
+            .or_else(|_| std::fs::read_to_string(storage::data_dir().join(OLD_SAVE_FILE)).map(|t| if t.starts_with(SAVE_HEADER) { t } else { String::new() }))
+            .unwrap_or_default();
+        let mut lines = text.lines();
+        match lines.next() {
+            None => {}
+            Some(SAVE_HEADER) => for l in lines {
+                match l.split('\t').collect::<Vec<_>>()[..] {
+                    ["metric", m, v] if v.parse::<u32>().is_ok() => { progress.insert(m.to_owned(), v.parse().unwrap_or(0)); }
+                    ["unlocked", id, date] => { unlocked.insert(id.to_owned(), date.to_owned()); }
+                    _ => warnings.push(format!("skipped unreadable achievement progress line `{}`", l)),
+                }
+            },
+            Some(_) => warnings.push("achievement progress is from another version; starting over".to_owned()),
+        }
+        Achievements { defs, progress, unlocked, toasts: vec![], clean_wave: true, warnings }
+    }
+
+    fn save_path() -> std::path::PathBuf { storage::data_dir().join(SAVE_FILE) }
+
+    pub fn save(&mut self) {
+        let mut text = format!("{}\n", SAVE_HEADER);
+        let mut metrics: Vec<_> = self.progress.iter().collect();
+        metrics.sort();
+        for (m, v) in metrics { text += &format!("metric\t{}\t{}\n", m, v); }
+        let mut ids: Vec<_> = self.unlocked.iter().collect();
+        ids.sort();
+        for (id, date) in ids { text += &format!("unlocked\t{}\t{}\n", id, date); }
+        if let Err(e) = storage::write_atomic(&Achievements::save_path(), &text) { eprintln!("couldn't save achievements: {}", e); }
+    }
+
+    // lifetime metric
+    pub fn add(&mut self, metric: &str, n: u32) {
+        *self.progress.entry(metric.to_owned()).or_insert(0) += n;
+        self.check(metric);
+    }
+
+    // best-in-one-go metric
+    pub fn reach(&mut self, metric: &str, v: u32) {
+        let p = self.progress.entry(metric.to_owned()).or_insert(0);
+        if v <= *p { return; }
+        *p = v;
+        self.check(metric);
+    }
+
+    fn check(&mut self, metric: &str) {
+        let value = self.progress.get(metric).copied().unwrap_or(0);
+        let mut any = false;
+        for d in self.defs.iter().filter(|d| d.metric == metric && value >= d.target) {
+            if self.unlocked.contains_key(&d.id) { continue; }
+            self.unlocked.insert(d.id.clone(), storage::civil_date(storage::days_today()));
+            self.toasts.push((d.name.clone(), TOAST_TIME));
+            eprintln!("achievement unlocked: {}", d.name);
+            any = true;
+        }
+        if any { self.save(); }
+    }
+
+    pub fn draw_toasts(&mut self, dt: f32) {
+        for t in &mut self.toasts { t.1 -= dt; }
+        self.toasts.retain(|t| t.1 > 0.0);
+        set_default_camera();
+        for (i, (name, left)) in self.toasts.iter().enumerate() {
+            let a = (left / 0.4).min(1.0); // fade out
+            let (w, h) = (300.0, 54.0);
+            let (x, y) = (screen_width() - w - 16.0, 16.0 + i as f32 * (h + 8.0));
+            draw_rectangle(x, y, w, h, Color::new(0.1, 0.1, 0.14, 0.85 * a));
+            draw_rectangle_lines(x, y, w, h, 2.0, Color::new(1.0, 0.8, 0.2, a));
+            draw_text("Achievement unlocked", x + 12.0, y + 20.0, 18.0, Color::new(1.0, 0.8, 0.2, a));
+            draw_text(name, x + 12.0, y + 44.0, 26.0, Color::new(1.0, 1.0, 1.0, a));
+        }
+    }
+
+    pub fn draw_screen(&self) {
+        set_default_camera();
+        let title = format!("ACHIEVEMENTS  {}/{}", self.unlocked.keys().filter(|id| self.defs.iter().any(|d| &d.id == *id)).count(), self.defs.len());
+        let tw = measure_text(&title, None, 44, 1.0);
+        draw_text(&title, screen_width()*0.5 - tw.width*0.5, 80.0, 44.0, YELLOW);
+        let x = screen_width()*0.5 - 320.0;
+        let mut y = 130.0;
+        for d in &self.defs {
+            let value = self.progress.get(&d.metric).copied().unwrap_or(0).min(d.target);
+            let (col, status) = match self.unlocked.get(&d.id) {
+                Some(date) => (GOLD, format!("unlocked {}", date)),
+                None => (LIGHTGRAY, format!("{}/{}", value, d.target)),
+            };
+            draw_text(&d.name, x, y, 24.0, col);
+            draw_text(&d.desc, x + 250.0, y, 20.0, GRAY);
+            // progress bar
+            let frac = if self.unlocked.contains_key(&d.id) { 1.0 } else { value as f32 / d.target as f32 };
+            draw_rectangle(x + 250.0, y + 6.0, 240.0, 5.0, Color::from_rgba(50,50,60,255));
+            draw_rectangle(x + 250.0, y + 6.0, 240.0 * frac, 5.0, col);
+            draw_text(&status, x + 510.0, y, 20.0, col);
+            y += 34.0;
+        }
+        for w in &self.warnings { y += 24.0; draw_text(w, x, y, 20.0, ORANGE); }
+        let info = "Esc / C back";
+        let t = measure_text(info, None, 20, 1.0);
+        draw_text(info, screen_width()*0.5 - t.width*0.5, screen_height() - 18.0, 20.0, GRAY);
+    }
+}
+
+impl Listener for Achievements {
+    fn on_event(&mut self, e: &Event) {
+        match *e {
+            Event::ZombieKilled { kind, cause, chain, .. } => {
+                let name = KIND_NAMES[kind as usize];
+                self.add("kills", 1);
+                self.add(&format!("kills.{}", name), 1);
+                if cause == Cause::Bomb { self.add(&format!("bomb_kills.{}", name), 1); }
+                self.reach("combo_kills", chain);
+            }
+            Event::PlayerDamaged { .. } => self.clean_wave = false,
+            Event::PowerupPicked { .. } => self.add("powerups", 1),
+            Event::WaveStarted { wave, .. } => { self.clean_wave = true; self.reach("wave", wave); }
+            Event::WaveCleared { .. } if self.clean_wave => self.add("clean_waves", 1),
+            Event::GameOver { outcome, score, .. } => {
+                self.add("runs", 1);
+                self.reach("score", score);
+                if outcome == Outcome::Complete { self.add("missions", 1); } // only missions can be completed
+                self.save();
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+// ---- Arena: bounds, walls, destructible cover, spawn zones & pickup points ----
+// Loaded from a map file (see map.rs) or procedurally generated from a seed.
+// Layouts are checked on a 1-unit occupancy grid so we can flood-fill and
+// reject any piece of cover that would wall off part of the floor.
+// -------------------------------------------------------------------------------
+
+pub const COVER_SIZE: Vec3 = vec3(1.4, 1.2, 1.4);
+pub const COVER_HP: f32 = 12.0;
+
+const MIN_HALF: i32 = 18;
+const MAX_HALF: i32 = 32;
+const SPAWN_CLEAR: f32 = 5.0; // no cover this close to the player spawn
+const NAV_PAD: f32 = 0.75; // widest zombie radius (+ a bit) must fit through gaps
+pub const WALL_HEIGHT: f32 = 1.6;
+
+#[derive(Clone, Copy)]
+pub struct Cover { pub pos: Vec3, pub size: Vec3, pub hp: f32, pub max_hp: f32 }
+
+impl Cover {
+    pub fn new(x: f32, z: f32, size: Vec3, hp: f32) -> Self { Cover { pos: vec3(x, size.y * 0.5, z), size, hp, max_hp: hp } }
+}
+
+// indestructible, axis-aligned
+#[derive(Clone, Copy)]
+pub struct Wall { pub pos: Vec3, pub size: Vec3 }
+
+impl Wall {
+    pub fn from_segment(a: Vec2, b: Vec2, thickness: f32) -> Self {
+        let c = (a + b) * 0.5; let d = (b - a).abs();
+        Wall { pos: vec3(c.x, WALL_HEIGHT * 0.5, c.y), size: vec3(d.x + thickness, WALL_HEIGHT, d.y + thickness) }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct SpawnZone { pub pos: Vec2, pub radius: f32 }
+
+#[derive(Clone)]
+pub struct Arena {
+    pub name: String,
+    pub seed: Option<u64>, // procedurally generated layouts only
+    pub half: f32,
+    pub spawn: Vec2,
+    pub walls: Vec<Wall>,
+    pub covers: Vec<Cover>,
+    pub zones: Vec<SpawnZone>,
+    pub pickups: Vec<Vec2>,
+}
+
+impl Arena {
+    pub fn empty(name: &str, half: f32) -> Arena {
+        Arena { name: name.to_owned(), seed: None, half, spawn: Vec2::ZERO, walls: vec![], covers: vec![], zones: vec![], pickups: vec![] }
+    }
+
+    pub fn generate(seed: u64) -> Arena {
+        let rng = RandGenerator::new(); rng.srand(seed);
+        let half = rng.gen_range(MIN_HALF, MAX_HALF + 1) as f32;
+        let mut grid = Grid::new(half, Vec2::ZERO);
+        let mut covers: Vec<Cover> = vec![];
+
+        // try to place a piece; keep it only if the floor stays connected
+        let mut place = |c: Cover, covers: &mut Vec<Cover>| {
+            let h = c.size * 0.5;
+            if c.pos.x.abs() + h.x > half - 1.0 || c.pos.z.abs() + h.z > half - 1.0 { return; }
+            if vec2(c.pos.x, c.pos.z).length() - h.x.max(h.z) < SPAWN_CLEAR { return; }
+            if covers.iter().any(|o| overlaps(o, &c, 0.2)) { return; }
+            let saved = grid.cells.clone();
+            grid.block(c.pos, c.size);
+            if grid.connected() { covers.push(c); } else { grid.cells = saved; }
+        };
+
+        let area = (half / 6.0).powi(2);
+        // pillars: tall, tough single blocks
+        for _ in 0..(area * 0.8) as usize {
+            let (x, z) = (rng.gen_range(-half, half), rng.gen_range(-half, half));
+            place(Cover::new(x, z, vec3(1.2, 2.6, 1.2), COVER_HP * 2.0), &mut covers);
+        }
+        // clusters: a handful of crates around a point
+        for _ in 0..(area * 0.4) as usize {
+            let (cx, cz) = (rng.gen_range(-half, half), rng.gen_range(-half, half));
+            for _ in 0..rng.gen_range(3, 7) {
+                let (x, z) = (cx + rng.gen_range(-2.5, 2.5), cz + rng.gen_range(-2.5, 2.5));
+                place(Cover::new(x, z, COVER_SIZE, COVER_HP), &mut covers);
+            }
+        }
+        // corridors: two parallel low walls with a lane between them
+        for _ in 0..(area * 0.3) as usize {
+            let (cx, cz) = (rng.gen_range(-half, half), rng.gen_range(-half, half));
+            let len = rng.gen_range(5.0, 10.0); let lane = rng.gen_range(2.5, 4.0);
+            let along_x = rng.gen_range(0, 2) == 0;
+            for side in [-1.0, 1.0] {
+                let (x, z, size) = if along_x { (cx, cz + side * lane * 0.5, vec3(len, 1.0, 0.6)) } else { (cx + side * lane * 0.5, cz, vec3(0.6, 1.0, len)) };
+                place(Cover::new(x, z, size, COVER_HP * 1.5), &mut covers);
+            }
+        }
+        // zombies pour in from the four edges; pickups land on open floor
+        let zones = [(0.0, -1.0), (0.0, 1.0), (-1.0, 0.0), (1.0, 0.0)].iter().map(|&(x, z)| SpawnZone { pos: vec2(x, z) * (half - 3.5), radius: 2.5 }).collect();
+        let mut pickups = vec![];
+        for _ in 0..40 {
+            if pickups.len() == 4 { break; }
+            let p = vec2(rng.gen_range(-half + 3.0, half - 3.0), rng.gen_range(-half + 3.0, half - 3.0));
+            if p.length() > SPAWN_CLEAR && !covers.iter().any(|c| (vec2(c.pos.x, c.pos.z) - p).length() < c.size.x.max(c.size.z) + 1.0) { pickups.push(p); }
+        }
+        Arena { name: "Generated".to_owned(), seed: Some(seed), half, spawn: Vec2::ZERO, walls: vec![], covers, zones, pickups }
+    }
+
+    // every open floor cell reachable from the player spawn?
+    pub fn is_connected(&self) -> bool {
+        let mut grid = Grid::new(self.half, self.spawn);
+        for w in &self.walls { grid.block(w.pos, w.size); }
+        for c in &self.covers { grid.block(c.pos, c.size); }
+        grid.connected()
+    }
+
+    pub fn spawn_pos(&self) -> Vec3 { vec3(self.spawn.x, 0.5, self.spawn.y) }
+
+    // can a body of radius `r` stand here: inside the bounds and clear of walls and cover?
+    pub fn is_open(&self, p: Vec3, r: f32) -> bool {
+        if p.x.abs() + r > self.half || p.z.abs() + r > self.half { return false; }
+        let clear = |center: Vec3, size: Vec3| { let mut q = p; !push_out_of_box(&mut q, r, center, size) };
+        self.walls.iter().all(|w| clear(w.pos, w.size)) && self.covers.iter().all(|c| clear(c.pos, c.size))
+    }
+}
+
+pub fn random_seed() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(0) % 1_000_000_000
+}
+
+// circle (xz) vs box push-out; returns true when touching
+pub fn push_out_of_box(pos: &mut Vec3, r: f32, center: Vec3, size: Vec3) -> bool {
+    let h = size * 0.5;
+    let c = center;
+    let closest = vec2(pos.x.clamp(c.x - h.x, c.x + h.x), pos.z.clamp(c.z - h.z, c.z + h.z));
+    let d = vec2(pos.x, pos.z) - closest;
+    let dist = d.length();
+    if dist >= r { return false; }
+    let push = if dist > 1e-4 { d / dist * (r - dist) } else {
+        // center inside the box: shove out along the shallowest axis
+        let (ox, oz) = (h.x + r - (pos.x - c.x).abs(), h.z + r - (pos.z - c.z).abs());
+        if ox < oz { vec2(ox * (pos.x - c.x).signum(), 0.0) } else { vec2(0.0, oz * (pos.z - c.z).signum()) }
+    };
+    pos.x += push.x; pos.z += push.y;
+    true
+}
+
+fn overlaps(a: &Cover, b: &Cover, pad: f32) -> bool {
+    (a.pos.x - b.pos.x).abs() < (a.size.x + b.size.x) * 0.5 + pad && (a.pos.z - b.pos.z).abs() < (a.size.z + b.size.z) * 0.5 + pad
+}
+
+// 1x1 occupancy grid over the arena floor, true = blocked
+struct Grid { n: usize, half: f32, start: usize, cells: Vec<bool> }
+
+impl Grid {
+    fn new(half: f32, spawn: Vec2) -> Grid {
+        let n = (half * 2.0) as usize;
+        let cell = |v: f32| ((v + half) as usize).min(n - 1);
+        Grid { n, half, start: cell(spawn.y) * n + cell(spawn.x), cells: vec![false; n * n] }
+    }
+
+    fn block(&mut self, center: Vec3, size: Vec3) {
+        let h = size * 0.5 + vec3(NAV_PAD, 0.0, NAV_PAD);
+        for iz in 0..self.n { for ix in 0..self.n {
+            let (x, z) = (ix as f32 + 0.5 - self.half, iz as f32 + 0.5 - self.half);
+            if (x - center.x).abs() < h.x && (z - center.z).abs() < h.z { self.cells[iz * self.n + ix] = true; }
+        } }
+    }
+
+    // every open cell reachable from the spawn?
+    fn connected(&self) -> bool {
+        let n = self.n; let start = self.start;
+        if self.cells[start] { return false; }
+        let mut seen = vec![false; n * n]; seen[start] = true;
+        let mut stack = vec![start];
+        while let Some(i) = stack.pop() {
+            let (ix, iz) = (i % n, i / n);
+            let mut visit = |j: usize| if !self.cells[j] && !seen[j] { seen[j] = true; stack.push(j); };
+            if ix > 0 { visit(i - 1); }
+            if ix + 1 < n { visit(i + 1); }
+            if iz > 0 { visit(i - n); }
+            if iz + 1 < n { visit(i + n); }
+        }
+        (0..n * n).all(|i| self.cells[i] || seen[i])
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::io::Write;
+
+use zombie_shooter::arena::Arena;
+use zombie_shooter::bot::Bot;
+use zombie_shooter::coop::Coop;
+use zombie_shooter::difficulty::{Difficulty, Preset, PRESETS};
+use zombie_shooter::events::{self, Event, Listener};
+use zombie_shooter::mode::{Mode, Outcome};
+use zombie_shooter::stats::{DROP_NAMES, KIND_NAMES};
+use zombie_shooter::{intermission, map, step, Game, Step, SPAWN_TELEGRAPH};
+
+// ---- Balance simulator: the bot plays thousands of headless runs ----
+// zombie_shooter_balance [--runs 250] [--presets easy,normal,hard,nightmare]
+//     [--mode waves] [--seed 1] [--map maps/x.map] [--max-secs 600]
+//     [--threads <n>] [--csv <file>]
+// Every preset plays the same seeds (--seed, --seed + 1, ...; each seed also
+// generates the arena unless --map fixes one), so presets and builds compare run
+// for run. The scripted bot (bot.rs) plays player 1 with the real rules at 60 Hz
+// until the run ends or --max-secs pass. Per preset it prints:
+//   waves reached   distribution, plus how many runs hit the time cap
+//   damage taken    HP lost per run to each ZKind
+//   time to kill    seconds from a zombie rising to its death, per ZKind
+//   powerups        pickups per run, and the kill / damage rate in the POWER_WINDOW
+//                   seconds after a pickup against that run's overall rate
+// --csv also writes one row per run, for a spreadsheet. Run it before and after
+// touching spawn_zombie's weights or wave_spawn_budget; a preset's numbers only
+// change when the rules (or the bot) do.
+// -----------------------------------------------
+
+const DT: f32 = 1.0 / 60.0;
+const POWER_WINDOW: f32 = 8.0; // rapid and slow last 6 s; a bomb or heal pays off soon after
+
+// what one run amounted to
+struct Run {
+    preset: Preset, seed: u64,
+    wave: u32, secs: f32, score: u32, outcome: Option<Outcome>,
+    kills: [u32; 3], damage: [u32; 3],
+    ttk: [(f32, u32); 3], // summed seconds, count
+    pickups: [u32; 4],
+    after: [(f32, f32, f32); 4], // kills, damage and seconds inside pickup windows
+}
+
+// the event stream, timed: when kills, hits and pickups happened, and how long
+// each zombie lasted once it had risen
+#[derive(Default)]
+struct Timeline { now: f32, kills: Vec<f32>, hurt: Vec<(f32, u32)>, pickups: Vec<(usize, f32)>, risen: HashMap<u32, f32>, ttk: [(f32, u32); 3] }
+
+impl Listener for Timeline {
+    fn on_event(&mut self, e: &Event) {
+        match *e {
+            Event::ZombieSpawned { id, .. } => { self.risen.insert(id, self.now + SPAWN_TELEGRAPH); }
+            Event::ZombieKilled { id, kind, .. } => {
+                self.kills.push(self.now);
+                if let Some(at) = self.risen.remove(&id) { self.ttk[kind as usize].0 += self.now - at; self.ttk[kind as usize].1 += 1; }
+            }
+            Event::GeneratorHit { id, .. } => { self.risen.remove(&id); }
+            Event::PlayerDamaged { zombie, amount, .. } => { self.risen.remove(&zombie); self.hurt.push((self.now, amount.max(0) as u32)); }
+            Event::PowerupPicked { kind, .. } => self.pickups.push((kind as usize, self.now)),
+            _ => {}
+        }
+    }
+}
+
+struct Config { runs: u64, presets: Vec<Preset>, mode: Mode, seed: u64, map: Option<Arena>, max_secs: f32, threads: usize, csv: Option<String> }
+
+impl Config {
+    fn from_args() -> Config {
+        let args: Vec<String> = std::env::args().collect();
+        let arg = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).cloned();
+        let num = |name: &str, default: f64| arg(name).map_or(default, |v| v.parse().unwrap_or_else(|_| fail(&format!("{} wants a number, got {}", name, v))));
+        let presets = arg("--presets").map_or_else(|| PRESETS[..4].to_vec(), |list| list.split(',').map(|k| {
+            PRESETS.into_iter().find(|p| Difficulty::preset(*p).key() == k).unwrap_or_else(|| fail(&format!("unknown preset {}", k)))
+        }).collect());
+        let map = arg("--map").map(|path| {
+            let text = std::fs::read_to_string(&path).unwrap_or_else(|e| fail(&format!("can't read {}: {}", path, e)));
+            map::parse(&text).unwrap_or_else(|e| fail(&format!("{}: {}", path, e)))
+        });
+        let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+        Config {
+            runs: (num("--runs", 250.0) as u64).max(1), presets,
+            mode: arg("--mode").map_or(Mode::Waves, |k| Mode::from_key(&k).unwrap_or_else(|| fail(&format!("unknown mode {}", k)))),
+            seed: num("--seed", 1.0) as u64, map, max_secs: num("--max-secs", 600.0) as f32,
+            threads: (num("--threads", cores as f64) as usize).max(1), csv: arg("--csv"),
+        }
+    }
+}
+
+fn fail(msg: &str) -> ! {
+    eprintln!("zombie_shooter_balance: {}", msg);
+    std::process::exit(2);
+}
+
+fn play(cfg: &Config, preset: Preset, seed: u64) -> Run {
+    let arena = cfg.map.clone().unwrap_or_else(|| Arena::generate(seed));
+    let mut g = Game::new(arena, cfg.mode, Difficulty::preset(preset), Coop::Solo, seed);
+    g.director.quiet = true;
+    let mut bot = Bot::new();
+    let mut line = Timeline::default();
+    let (mut between, mut over) = (false, false);
+    while !over && g.elapsed < cfg.max_secs {
+        if between { between = !intermission(&mut g, DT); }
+        else {
+            let input = bot.input(&g, 0, DT);
+            match step(&mut g, DT, &[input]) {
+                Step::Over => over = true, // its events still count
+                Step::WaveCleared => between = true,
+                Step::Playing => {}
+            }
+        }
+        line.now = g.elapsed;
+        let batch = std::mem::take(&mut g.events);
+        events::dispatch(&batch, &mut [&mut g.stats, &mut g.director, &mut line]);
+    }
+
+    let mut after = [(0.0, 0.0, 0.0); 4];
+    for (kind, at) in &line.pickups {
+        let within = |t: f32| t > *at && t <= at + POWER_WINDOW;
+        let a = &mut after[*kind];
+        a.0 += line.kills.iter().filter(|t| within(**t)).count() as f32;
+        a.1 += line.hurt.iter().filter(|(t, _)| within(*t)).map(|(_, n)| *n as f32).sum::<f32>();
+        a.2 += (g.elapsed - at).min(POWER_WINDOW);
+    }
+    Run {
+        preset, seed, wave: g.wave, secs: g.elapsed, score: g.score, outcome: g.outcome,
+        kills: g.stats.kills, damage: g.stats.damage, ttk: line.ttk, pickups: g.stats.powerups, after,
+    }
+}
+
+fn report(cfg: &Config, preset: Preset, runs: &[&Run]) {
+    let n = runs.len().max(1) as f32;
+    println!("\n== {} — {} runs of {}, seeds {}..{} ==", Difficulty::preset(preset).name(), runs.len(), cfg.mode.name(), cfg.seed, cfg.seed + cfg.runs - 1);
+
+    let mut waves: Vec<u32> = runs.iter().map(|r| r.wave).collect();
+    waves.sort_unstable();
+    let capped = runs.iter().filter(|r| r.outcome.is_none()).count();
+    let mean = waves.iter().sum::<u32>() as f32 / n;
+    println!("waves reached    min {}  median {}  mean {:.1}  max {}  ({} hit the {:.0}s cap)",
+        waves[0], waves[waves.len() / 2], mean, waves[waves.len() - 1], capped, cfg.max_secs);
+    let top = *waves.last().unwrap_or(&1);
+    for w in waves[0]..=top {
+        let count = waves.iter().filter(|v| **v == w).count();
+        if count == 0 { continue; }
+        let share = count as f32 / n;
+        println!("  wave {:>3}  {:>5.1}%  {}", w, share * 100.0, "#".repeat((share * 50.0).round() as usize));
+    }
+    let outcomes = [Outcome::Died, Outcome::TimeUp, Outcome::Complete, Outcome::Failed]
+        .map(|o| format!("{:?} {}", o, runs.iter().filter(|r| r.outcome == Some(o)).count()));
+    println!("outcomes         {}", outcomes.join("  "));
+
+    let per_kind = |f: &dyn Fn(&Run, usize) -> f32| KIND_NAMES.iter().enumerate().map(|(k, name)| format!("{} {:.2}", name, runs.iter().map(|r| f(r, k)).sum::<f32>() / n)).collect::<Vec<_>>().join("  ");
+    println!("damage taken     {}  (HP per run)", per_kind(&|r, k| r.damage[k] as f32));
+    println!("kills            {}  (per run)", per_kind(&|r, k| r.kills[k] as f32));
+    let ttk: Vec<String> = KIND_NAMES.iter().enumerate().map(|(k, name)| {
+        let (secs, count) = runs.iter().fold((0.0, 0), |(s, c), r| (s + r.ttk[k].0, c + r.ttk[k].1));
+        if count == 0 { format!("{} -", name) } else { format!("{} {:.2}s", name, secs / count as f32) }
+    }).collect();
+    println!("time to kill     {}", ttk.join("  "));
+
+    // per minute, over every run together: after a pickup vs any time
+    let (kills, damage, secs) = runs.iter().fold((0.0, 0.0, 0.0), |(k, d, s), r| (k + r.kills.iter().sum::<u32>() as f32, d + r.damage.iter().sum::<u32>() as f32, s + r.secs));
+    let rate = |v: f32, t: f32| if t > 0.0 { v / t * 60.0 } else { 0.0 };
+    println!("powerups         per run   kills/min after (all: {:.1})   damage/min after (all: {:.2})", rate(kills, secs), rate(damage, secs));
+    for (d, name) in DROP_NAMES.iter().enumerate() {
+        let (k, h, t) = runs.iter().fold((0.0, 0.0, 0.0), |(k, h, t), r| (k + r.after[d].0, h + r.after[d].1, t + r.after[d].2));
+        let picked = runs.iter().map(|r| r.pickups[d]).sum::<u32>() as f32 / n;
+        if t <= 0.0 { println!("  {:<6}         {:>6.2}   -", name, picked); continue; }
+        let vs = |v: f32, all: f32| if all > 0.0 { format!("{:+.0}%", (v / all - 1.0) * 100.0) } else { "-".to_owned() };
+        println!("  {:<6}         {:>6.2}   {:>5.1} ({:>5})                {:>5.2} ({:>5})", name, picked,
+            rate(k, t), vs(rate(k, t), rate(kills, secs)), rate(h, t), vs(rate(h, t), rate(damage, secs)));
+    }
+}
+
+fn write_csv(path: &str, runs: &[Run]) -> std::io::Result<()> {
+    let mut f = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut head: Vec<String> = ["preset", "seed", "wave", "seconds", "score", "outcome"].map(String::from).to_vec();
+    head.extend(KIND_NAMES.iter().map(|k| format!("kills_{}", k)));
+    head.extend(KIND_NAMES.iter().map(|k| format!("damage_{}", k)));
+    head.extend(KIND_NAMES.iter().map(|k| format!("ttk_{}", k)));
+    head.extend(DROP_NAMES.iter().map(|d| format!("powerup_{}", d)));
+    writeln!(f, "{}", head.join(","))?;
+    for r in runs {
+        let mut cols = vec![Difficulty::preset(r.preset).key(), r.seed.to_string(), r.wave.to_string(), format!("{:.1}", r.secs), r.score.to_string(),
+            r.outcome.map_or("cap".to_owned(), |o| format!("{:?}", o).to_lowercase())];
+        cols.extend(r.kills.iter().map(|v| v.to_string()));
+        cols.extend(r.damage.iter().map(|v| v.to_string()));
+        cols.extend(r.ttk.iter().map(|(s, c)| if *c == 0 { String::new() } else { format!("{:.2}", s / *c as f32) }));
+        cols.extend(r.pickups.iter().map(|v| v.to_string()));
+        writeln!(f, "{}", cols.join(","))?;
+    }
+    Ok(())
+}
+
+fn main() {
+    let cfg = Config::from_args();
+    let jobs: Vec<(Preset, u64)> = cfg.presets.iter().flat_map(|p| (0..cfg.runs).map(move |i| (*p, i))).collect();
+    eprintln!("{} runs on {} thread(s)…", jobs.len(), cfg.threads);
+    let started = std::time::Instant::now();
+    // every thread takes every threads-th job, so slow presets don't pile up on one
+    let runs: Vec<Run> = std::thread::scope(|s| {
+        let workers: Vec<_> = (0..cfg.threads).map(|t| {
+            let (cfg, jobs) = (&cfg, &jobs);
+            s.spawn(move || jobs.iter().skip(t).step_by(cfg.threads).map(|(p, i)| play(cfg, *p, cfg.seed + i)).collect::<Vec<Run>>())
+        }).collect();
+        workers.into_iter().flat_map(|w| w.join().unwrap_or_else(|_| fail("a run panicked"))).collect()
+    });
+    eprintln!("done in {:.1}s", started.elapsed().as_secs_f32());
+
+    for p in &cfg.presets {
+        let mine: Vec<&Run> = runs.iter().filter(|r| r.preset == *p).collect();
+        report(&cfg, *p, &mine);
+    }
+    if let Some(path) = &cfg.csv {
+        let mut sorted: Vec<Run> = runs;
+        sorted.sort_by_key(|r| (cfg.presets.iter().position(|p| *p == r.preset), r.seed));
+        match write_csv(path, &sorted) { Ok(()) => eprintln!("per-run rows written to {}", path), Err(e) => eprintln!("can't write {}: {}", path, e) }
+    }
+}
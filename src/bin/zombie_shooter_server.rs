@@ -0,0 +1,201 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use zombie_shooter::arena::{self, Arena};
+use zombie_shooter::coop::Coop;
+use zombie_shooter::difficulty::{Difficulty, PRESETS};
+use zombie_shooter::events::{self, Logger};
+use zombie_shooter::input::PlayerInput;
+use zombie_shooter::mode::{Mode, Outcome};
+use zombie_shooter::net::{self, Join, Link, Phase, Seats};
+use zombie_shooter::{clock, intermission, map, step, storage, Game, Step};
+
+// ---- Dedicated server: co-op runs for network clients, without a window ----
+// zombie_shooter_server [--port 7777] [--lobbies 4] [--players 2] [--separate]
+//     [--mode waves] [--difficulty normal] [--map maps/x.map | --seed <n>]
+//     [--log <file>] [--lag <ms>] [--loss <%>] [--log-events]
+// Clients connect with the game's `--join <host>:<port>` and take the first free
+// seat in any lobby; when every lobby is full a new one opens, up to --lobbies.
+// A lobby's run starts once all --players seats are taken and everyone has the
+// run loaded, then advances at TICK_RATE with the same rules as the game: waves,
+// intermissions, missions and outcomes. Whoever drops mid-run leaves their player
+// standing there and the seat open for someone new. When a run ends its result
+// goes to stdout and is appended to the log file (<data dir>/server.log unless
+// --log says otherwise); RESULTS_TIME later a fresh run is set up for whoever is
+// still there. Spectators (`--spectate`) watch the first lobby with room for them.
+// A lobby closes when its last player leaves.
+// -----------------------------------------------
+
+const TICK_RATE: f64 = 60.0;
+const RESULTS_TIME: f32 = 10.0; // seconds on the results screen before the next run
+
+struct Config {
+    port: u16, lobbies: usize,
+    mode: Mode, diff: Difficulty, coop: Coop,
+    map: Option<Arena>, seed: Option<u64>, // a fixed layout; otherwise every run generates one
+    log: PathBuf,
+    lag: f32, loss: f32, log_events: bool,
+}
+
+impl Config {
+    fn from_args() -> Config {
+        let args: Vec<String> = std::env::args().collect();
+        let arg = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).cloned();
+        let num = |name: &str, default: f64| arg(name).map_or(default, |v| v.parse().unwrap_or_else(|_| fail(&format!("{} wants a number, got {}", name, v))));
+        let mode = arg("--mode").map_or(Mode::Waves, |k| Mode::from_key(&k).unwrap_or_else(|| fail(&format!("unknown mode {}", k))));
+        let diff = arg("--difficulty").map_or(Difficulty::preset(PRESETS[1]), |k| {
+            PRESETS.iter().map(|p| Difficulty::preset(*p)).find(|d| d.key() == k).unwrap_or_else(|| fail(&format!("unknown difficulty {}", k)))
+        });
+        let coop = match num("--players", 2.0) as usize {
+            1 => Coop::Solo,
+            2 if args.iter().any(|a| a == "--separate") => Coop::Separate,
+            2 => Coop::Shared,
+            n => fail(&format!("a run has 1 or 2 players, not {}", n)),
+        };
+        let map = arg("--map").map(|path| {
+            let text = std::fs::read_to_string(&path).unwrap_or_else(|e| fail(&format!("can't read {}: {}", path, e)));
+            map::parse(&text).unwrap_or_else(|e| fail(&format!("{}: {}", path, e)))
+        });
+        Config {
+            port: num("--port", 7777.0) as u16, lobbies: (num("--lobbies", 4.0) as usize).max(1),
+            mode, diff, coop, map, seed: arg("--seed").map(|v| v.parse().unwrap_or_else(|_| fail("--seed wants a number"))),
+            log: arg("--log").map_or_else(|| storage::data_dir().join("server.log"), PathBuf::from),
+            lag: num("--lag", 0.0) as f32, loss: num("--loss", 0.0) as f32, log_events: args.iter().any(|a| a == "--log-events"),
+        }
+    }
+
+    fn new_run(&self) -> Game {
+        let arena = self.map.clone().unwrap_or_else(|| Arena::generate(self.seed.unwrap_or_else(arena::random_seed)));
+        Game::new(arena, self.mode, self.diff, self.coop, arena::random_seed())
+    }
+}
+
+fn fail(msg: &str) -> ! {
+    eprintln!("zombie_shooter_server: {}", msg);
+    std::process::exit(2);
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Stage { Waiting, Playing, Intermission, Results(f32) } // Results: seconds left on that screen
+
+struct Lobby { id: u32, game: Game, seats: Seats, stage: Stage }
+
+impl Lobby {
+    fn new(id: u32, cfg: &Config) -> Lobby {
+        println!("lobby {}: opened", id);
+        Lobby { id, game: cfg.new_run(), seats: Seats::new((0..cfg.coop.players()).collect(), true), stage: Stage::Waiting }
+    }
+
+    fn phase(&self) -> Phase {
+        match self.stage { Stage::Waiting => Phase::Lobby, Stage::Playing => Phase::Playing, Stage::Intermission => Phase::Intermission, Stage::Results(_) => Phase::Over }
+    }
+
+    // a run is under way (for the log when everyone leaves)
+    fn live(&self) -> bool { matches!(self.stage, Stage::Playing | Stage::Intermission) }
+
+    fn tick(&mut self, dt: f32, cfg: &Config, log: &mut Logger) {
+        let g = &mut self.game;
+        match self.stage {
+            Stage::Waiting => if self.seats.is_full() && self.seats.in_run(g) {
+                println!("lobby {}: run started, {} on {}", self.id, g.mode.name(), g.diff.name());
+                self.stage = Stage::Playing;
+            },
+            Stage::Playing => {
+                // nobody in a seat: that player just stands there
+                let inputs: Vec<PlayerInput> = (0..g.players.len()).map(|i| self.seats.input(i).unwrap_or_default()).collect();
+                match step(g, dt, &inputs) {
+                    Step::WaveCleared => self.stage = Stage::Intermission,
+                    Step::Over => { record(cfg, &result_line(self.id, g, &self.seats)); self.stage = Stage::Results(RESULTS_TIME); }
+                    Step::Playing => {}
+                }
+            }
+            Stage::Intermission => if intermission(g, dt) { self.stage = Stage::Playing; },
+            Stage::Results(left) if left > dt => self.stage = Stage::Results(left - dt),
+            Stage::Results(_) => { *g = cfg.new_run(); self.stage = Stage::Waiting; }
+        }
+        let batch = std::mem::take(&mut g.events);
+        events::dispatch(&batch, &mut [&mut g.stats, &mut g.director, log]);
+    }
+}
+
+// one line per finished (or abandoned) run
+fn result_line(lobby: u32, g: &Game, seats: &Seats) -> String {
+    let how = match g.outcome {
+        Some(Outcome::Died) => "everyone down",
+        Some(Outcome::TimeUp) => "time up",
+        Some(Outcome::Complete) => "mission complete",
+        Some(Outcome::Failed) => "mission failed",
+        None => "abandoned",
+    };
+    let map = match g.arena.seed { Some(seed) => format!("seed {}", seed), None => g.arena.name.clone() };
+    let split: Vec<String> = g.players.iter().enumerate().map(|(i, p)| format!("P{} {}", i + 1, p.score)).collect();
+    let players: Vec<String> = seats.addrs().iter().map(|a| a.to_string()).collect();
+    format!("lobby {}: {} / {} on {}: {} on wave {} after {}, score {} ({}), {} kills, players {}",
+        lobby, g.mode.name(), g.diff.name(), map, how, g.wave, clock(g.elapsed), g.score, split.join(", "), g.stats.kills.iter().sum::<u32>(),
+        if players.is_empty() { "none left".to_owned() } else { players.join(" ") })
+}
+
+fn record(cfg: &Config, line: &str) {
+    let secs = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let line = format!("{} {:02}:{:02}:{:02} {}", storage::civil_date((secs / 86_400) as i64), secs / 3600 % 24, secs / 60 % 60, secs % 60, line);
+    println!("{}", line);
+    let append = || -> std::io::Result<()> {
+        if let Some(dir) = cfg.log.parent() { std::fs::create_dir_all(dir)?; }
+        writeln!(std::fs::OpenOptions::new().create(true).append(true).open(&cfg.log)?, "{}", line)
+    };
+    if let Err(e) = append() { eprintln!("can't write {}: {}", cfg.log.display(), e); }
+}
+
+fn main() {
+    let cfg = Config::from_args();
+    let mut link = Link::bind(&format!("0.0.0.0:{}", cfg.port), cfg.lag, cfg.loss).unwrap_or_else(|e| fail(&format!("can't listen on UDP port {}: {}", cfg.port, e)));
+    println!("serving {} / {} for {} on UDP port {}, up to {} lobbies; results go to {}",
+        cfg.mode.name(), cfg.diff.name(), cfg.coop.name(), cfg.port, cfg.lobbies, cfg.log.display());
+    let mut log = Logger { enabled: cfg.log_events };
+    let mut lobbies: Vec<Lobby> = vec![];
+    let mut next_id = 1;
+    let dt = 1.0 / TICK_RATE;
+    let mut next_tick = Instant::now();
+
+    loop {
+        for (from, bytes) in link.recv() {
+            if let Some(l) = lobbies.iter_mut().find(|l| l.seats.has(from)) {
+                let phase = l.phase();
+                l.seats.receive(from, &bytes, &mut l.game, Some(phase));
+                continue;
+            }
+            let Some(join) = net::join_request(&bytes) else { continue; };
+            // the first free seat, else a new lobby (not just to watch), else sorry
+            let mut seated = false;
+            for l in &mut lobbies {
+                let phase = l.phase();
+                if l.seats.receive(from, &bytes, &mut l.game, Some(phase)) { seated = true; break; }
+            }
+            if seated { continue; }
+            if join == Join::Play && lobbies.len() < cfg.lobbies {
+                let mut l = Lobby::new(next_id, &cfg);
+                next_id += 1;
+                l.seats.receive(from, &bytes, &mut l.game, Some(Phase::Lobby));
+                lobbies.push(l);
+            } else { link.refuse(from); }
+        }
+
+        for l in &mut lobbies {
+            l.tick(dt as f32, &cfg, &mut log);
+            let phase = l.phase();
+            l.seats.update(&mut link, &l.game, Some(phase));
+        }
+        lobbies.retain(|l| {
+            if l.seats.count() > 0 { return true; }
+            if l.live() { record(&cfg, &result_line(l.id, &l.game, &l.seats)); }
+            println!("lobby {}: closed", l.id);
+            false
+        });
+
+        // fixed rate; if a tick ran long, carry on from now rather than rushing to catch up
+        next_tick += Duration::from_secs_f64(dt);
+        let now = Instant::now();
+        if next_tick > now { std::thread::sleep(next_tick - now); } else { next_tick = now; }
+    }
+}
@@ -0,0 +1,117 @@
+use macroquad::prelude::*;
+
+use crate::arena::Arena;
+use crate::input::PlayerInput;
+use crate::mission::Objective;
+use crate::{Drop, Game, ZKind};
+
+// ---- Scripted bot: a player made of the same inputs a human produces ----
+// Bot::input() looks at the run and answers with a PlayerInput for one player,
+// which goes through step() like anyone's keyboard or pad. It aims (no assist) at
+// the biggest threat: the closest, fastest zombie, with tanks counting extra since
+// they take several hits. It kites by backing away from everything within DANGER
+// while circling sideways (and turning round when a wall stops it), steers clear of
+// walls, cover and the arena edge, grabs drops it can use, picks up a downed
+// teammate and goes for the mission objective. It sprints when something is close.
+// Deterministic for a given run, so the same seed plays out the same way. The game
+// uses it for the attract demo and `--autoplay` (main.rs), the balance simulator for
+// thousands of headless runs; env.rs users can take it as a baseline to beat.
+// -----------------------------------------------
+
+const RANGE: f32 = 22.0; // opens fire on anything closer
+const DANGER: f32 = 8.0; // backs away from zombies inside this
+const CLOSE: f32 = 4.0; // sprints when one is this close
+const WALL_MARGIN: f32 = 1.8;
+const STUCK_CHECK: f32 = 0.75; // seconds between "have we moved?" checks
+
+pub struct Bot { circle: f32, last: Vec3, check: f32 } // circle: +1 / -1, which way round it kites
+
+impl Bot {
+    pub fn new() -> Bot { Bot { circle: 1.0, last: Vec3::ZERO, check: STUCK_CHECK } }
+
+    pub fn input(&mut self, g: &Game, i: usize, dt: f32) -> PlayerInput {
+        let Some(me) = g.players.get(i).filter(|p| !p.downed) else { return PlayerInput::default(); };
+        let at = flat(me.pos);
+
+        // target: the biggest threat in range, rising ones included so it's ready for them
+        let threat = |kind: ZKind, speed: f32, d: f32| speed * if kind == ZKind::Tank { 2.0 } else { 1.0 } / d.max(0.5);
+        let target = g.zombies.iter().map(|z| (z, flat(z.pos) - at)).filter(|(_, d)| d.length() < RANGE)
+            .max_by(|a, b| threat(a.0.kind, a.0.speed, a.1.length()).total_cmp(&threat(b.0.kind, b.0.speed, b.1.length())));
+
+        // movement: away from danger, round it, towards whatever is worth having
+        let mut away = Vec2::ZERO;
+        let mut nearest = f32::MAX;
+        for z in &g.zombies {
+            let d = at - flat(z.pos);
+            let len = d.length();
+            nearest = nearest.min(len);
+            if len < DANGER { away += d.normalize_or_zero() * ((DANGER - len) / DANGER).powi(2) * if z.kind == ZKind::Tank { 1.5 } else { 1.0 }; }
+        }
+        let mut dir = away * 3.0;
+        if away.length() > 0.05 { dir += away.normalize().perp() * self.circle * 1.2; }
+        // the closer the zombies, the less the goal matters
+        let to_goal = goal(g, i, at) - at;
+        if to_goal.length() > 0.3 { dir += to_goal.normalize() * (1.0 - away.length().min(1.0) * 0.7); }
+        dir += keep_off(&g.arena, at);
+
+        // going nowhere (pinned to a wall, say): go round the other way
+        self.check -= dt;
+        if self.check <= 0.0 {
+            if (me.pos - self.last).length() < 0.5 && away.length() > 0.05 { self.circle = -self.circle; }
+            self.last = me.pos; self.check = STUCK_CHECK;
+        }
+
+        PlayerInput {
+            move_dir: if dir.length() > 0.1 { dir.normalize() } else { Vec2::ZERO },
+            sprint: nearest < CLOSE,
+            fire: target.is_some_and(|(z, _)| z.rise <= 0.0),
+            aim: target.map(|(_, d)| d.normalize_or_zero()),
+            assist: false,
+        }
+    }
+}
+
+impl Default for Bot {
+    fn default() -> Bot { Bot::new() }
+}
+
+// where player i wants to be when nothing's in the way: a teammate to revive, the
+// objective, a useful drop, else the middle of the arena
+fn goal(g: &Game, i: usize, at: Vec2) -> Vec2 {
+    let closest = |points: &mut dyn Iterator<Item = Vec2>| points.min_by(|a, b| (*a - at).length().total_cmp(&(*b - at).length()));
+    if let Some(p) = closest(&mut g.players.iter().enumerate().filter(|(j, p)| *j != i && p.downed).map(|(_, p)| flat(p.pos))) { return p; }
+    let hurt = g.players[i].hp < g.max_hp;
+    let drop = closest(&mut g.drops.iter().filter(|d| d.kind != Drop::Heal || hurt).map(|d| flat(d.pos)));
+    let objective = match &g.objective {
+        Some(Objective::Hold { pos, .. }) => Some(*pos),
+        // close enough to draw them off it, not so close they chew on it while chasing us
+        Some(Objective::Generator { pos, .. }) => Some(*pos + if at == *pos { Vec2::X } else { (at - *pos).normalize() } * 3.0),
+        Some(Objective::Scavenge { items, extract, .. }) => closest(&mut items.iter().copied()).or(Some(*extract)),
+        None => None,
+    };
+    // a drop on the way is worth a detour; otherwise the objective comes first
+    match (objective, drop) {
+        (Some(o), Some(d)) if (d - at).length() < 6.0 => if (d - at).length() < (o - at).length() { d } else { o },
+        (Some(o), _) => o,
+        (None, Some(d)) => d,
+        (None, None) => Vec2::ZERO, // the open middle, away from the edges
+    }
+}
+
+// push away from walls, cover and the arena edge once within WALL_MARGIN of them
+fn keep_off(arena: &Arena, at: Vec2) -> Vec2 {
+    let mut push = Vec2::ZERO;
+    let boxes = arena.walls.iter().map(|w| (w.pos, w.size)).chain(arena.covers.iter().map(|c| (c.pos, c.size)));
+    for (pos, size) in boxes {
+        let half = vec2(size.x, size.z) * 0.5;
+        let d = at - at.clamp(flat(pos) - half, flat(pos) + half);
+        let len = d.length();
+        if len < WALL_MARGIN && len > 0.0 { push += d / len * (WALL_MARGIN - len) / WALL_MARGIN; }
+    }
+    let edge = arena.half - 1.0 - WALL_MARGIN;
+    push.x -= (at.x - at.x.clamp(-edge, edge)) / WALL_MARGIN;
+    push.y -= (at.y - at.y.clamp(-edge, edge)) / WALL_MARGIN;
+    push * 2.0
+}
+
+fn flat(p: Vec3) -> Vec2 { vec2(p.x, p.z) }
@@ -0,0 +1,53 @@
+use macroquad::prelude::*;
+
+use crate::gamepad;
+use crate::input::Source;
+
+// ---- Local co-op: two players on one screen ----
+// Player 1 plays on keyboard/mouse (plus any extra pads), player 2 on the last
+// connected pad, so it's keyboard+pad or two pads. Zombies go for the nearest
+// player still standing. A player at 0 HP is downed rather than dead: stand next
+// to them for REVIVE_TIME to get them back up, and anyone still down gets up when
+// the wave ends. The run is over once everyone is down. Scores are either one team
+// total or kept per player (the total still goes on the boards). The camera
+// frames both players, pulling back as they spread out, and splits the screen
+// side by side once they're too far apart to share it.
+// -----------------------------------------------
+
+pub const REVIVE_RADIUS: f32 = 1.8;
+pub const REVIVE_TIME: f32 = 3.0;
+const SPLIT_AT: f32 = 18.0; // player distance where one camera stops working
+const MAX_PULL_BACK: f32 = 0.7; // extra camera distance (fraction) just before the split
+
+pub const COLORS: [Color; 2] = [SKYBLUE, ORANGE];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Coop { Solo, Shared, Separate }
+
+impl Coop {
+    pub fn name(self) -> &'static str {
+        match self { Coop::Solo => "1 player", Coop::Shared => "2 players, team score", Coop::Separate => "2 players, separate scores" }
+    }
+    pub fn next(self) -> Coop { match self { Coop::Solo => Coop::Shared, Coop::Shared => Coop::Separate, Coop::Separate => Coop::Solo } }
+    pub fn players(self) -> usize { if self == Coop::Solo { 1 } else { 2 } }
+
+    // input devices per player, re-read every frame so pads can be plugged in mid-run
+    pub fn sources(self) -> Vec<Source> {
+        if self == Coop::Solo { return vec![Source::All]; }
+        let last = gamepad::count().max(1) - 1;
+        vec![Source::AllBut(last), Source::Pad(last)]
+    }
+}
+
+// where the camera(s) look: one shared view (zoom > 1 pulls it back) or a split
+pub enum View { Shared { focus: Vec3, zoom: f32 }, Split([Vec3; 2]) }
+
+pub fn view(players: &[Vec3]) -> View {
+    match players {
+        [a, b] => {
+            let d = (*a - *b).with_y(0.0).length();
+            if d >= SPLIT_AT { View::Split([*a, *b]) } else { View::Shared { focus: (*a + *b) * 0.5, zoom: 1.0 + MAX_PULL_BACK * d / SPLIT_AT } }
+        }
+        _ => View::Shared { focus: players.first().copied().unwrap_or(Vec3::ZERO), zoom: 1.0 },
+    }
+}
@@ -0,0 +1,77 @@
+use macroquad::rand::RandGenerator;
+
+use crate::storage;
+
+// ---- Daily challenge: the UTC date picks the seed and the modifiers ----
+// Everyone playing on the same day gets the same generated arena, the same
+// spawn/drop sequence (see SimRng in lib.rs) and the same rule twists, so the
+// per-day leaderboard compares like with like. Boards live in
+// <data dir>/daily/<date>.txt, one `score<TAB>wave<TAB>seconds` line per run.
+// ------------------------------------------------------------------------
+
+const BOARD_SIZE: usize = 10;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Modifier { OnlyFast, NoHeals, DoubleCombo, TankRush, TriggerHappy }
+
+pub const MODIFIERS: [Modifier; 5] = [Modifier::OnlyFast, Modifier::NoHeals, Modifier::DoubleCombo, Modifier::TankRush, Modifier::TriggerHappy];
+
+impl Modifier {
+    pub fn name(self) -> &'static str {
+        match self {
+            Modifier::OnlyFast => "Only fast zombies",
+            Modifier::NoHeals => "No Heal drops",
+            Modifier::DoubleCombo => "Double combo",
+            Modifier::TankRush => "Tank rush",
+            Modifier::TriggerHappy => "Trigger happy (faster fire)",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Daily { pub date: String, pub seed: u64, pub mods: Vec<Modifier> }
+
+#[derive(Clone, Copy)]
+pub struct Entry { pub score: u32, pub wave: u32, pub secs: u32 }
+
+impl Daily {
+    pub fn today() -> Daily {
+        Daily::for_day(storage::days_today())
+    }
+
+    pub fn for_day(days: i64) -> Daily {
+        let seed = (days as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 34;
+        let rng = RandGenerator::new(); rng.srand(seed);
+        let mut mods = vec![];
+        for _ in 0..rng.gen_range(1, 3) {
+            let m = MODIFIERS[rng.gen_range(0, MODIFIERS.len())];
+            if !mods.contains(&m) { mods.push(m); }
+        }
+        Daily { date: storage::civil_date(days), seed, mods }
+    }
+
+    pub fn has(&self, m: Modifier) -> bool { self.mods.contains(&m) }
+
+    fn board_path(&self) -> std::path::PathBuf { storage::data_dir().join("daily").join(format!("{}.txt", self.date)) }
+
+    // best first; unreadable lines are skipped
+    pub fn board(&self) -> Vec<Entry> {
+        let text = std::fs::read_to_string(self.board_path()).unwrap_or_default();
+        let mut out: Vec<Entry> = text.lines().filter_map(|l| {
+            let f: Vec<u32> = l.split('\t').map(|t| t.trim().parse()).collect::<Result<_, _>>().ok()?;
+            (f.len() == 3).then(|| Entry { score: f[0], wave: f[1], secs: f[2] })
+        }).collect();
+        out.sort_by(|a, b| b.score.cmp(&a.score).then(a.secs.cmp(&b.secs)));
+        out.truncate(BOARD_SIZE);
+        out
+    }
+
+    pub fn record(&self, e: Entry) -> std::io::Result<()> {
+        let mut board = self.board();
+        board.push(e);
+        board.sort_by(|a, b| b.score.cmp(&a.score).then(a.secs.cmp(&b.secs)));
+        board.truncate(BOARD_SIZE);
+        let text: String = board.iter().map(|e| format!("{}\t{}\t{}\n", e.score, e.wave, e.secs)).collect();
+        storage::write_atomic(&self.board_path(), &text)
+    }
+}
@@ -0,0 +1,59 @@
+// ---- Difficulty: presets are just a set of multipliers on the base tuning ----
+// Custom starts from Normal and lets every multiplier be tweaked on the map
+// select screen. `key()` is what gets stored next to scores.
+// -------------------------------------------------------------------------------
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Preset { Easy, Normal, Hard, Nightmare, Custom }
+
+pub const PRESETS: [Preset; 5] = [Preset::Easy, Preset::Normal, Preset::Hard, Preset::Nightmare, Preset::Custom];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Difficulty {
+    pub preset: Preset,
+    pub player_hp: f32,
+    pub zombie_speed: f32,
+    pub zombie_hp: f32,
+    pub spawn_budget: f32, // zombies per wave
+    pub drop_chance: f32,
+    pub wave_heal: f32, // HP restored between waves
+}
+
+impl Difficulty {
+    pub fn preset(preset: Preset) -> Difficulty {
+        let m = |player_hp, zombie_speed, zombie_hp, spawn_budget, drop_chance, wave_heal| Difficulty { preset, player_hp, zombie_speed, zombie_hp, spawn_budget, drop_chance, wave_heal };
+        match preset {
+            Preset::Easy => m(1.5, 0.85, 1.0, 0.8, 1.4, 2.0),
+            Preset::Normal | Preset::Custom => m(1.0, 1.0, 1.0, 1.0, 1.0, 1.0),
+            Preset::Hard => m(0.84, 1.15, 1.34, 1.25, 0.8, 1.0),
+            Preset::Nightmare => m(0.5, 1.3, 1.67, 1.5, 0.6, 0.0),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self.preset { Preset::Easy => "Easy", Preset::Normal => "Normal", Preset::Hard => "Hard", Preset::Nightmare => "Nightmare", Preset::Custom => "Custom" }
+    }
+
+    // presets are keyed by name; custom rules by their multipliers so different rule sets don't share a table
+    pub fn key(&self) -> String {
+        match self.preset {
+            Preset::Custom => format!("custom:{}", self.fields().iter().map(|(_, v)| format!("{:.2}", v)).collect::<Vec<_>>().join("/")),
+            _ => self.name().to_lowercase(),
+        }
+    }
+
+    // (label, value) for each multiplier, in display order
+    pub fn fields(&self) -> [(&'static str, f32); 6] {
+        [("Player HP", self.player_hp), ("Zombie speed", self.zombie_speed), ("Zombie HP", self.zombie_hp),
+         ("Zombies per wave", self.spawn_budget), ("Drop chance", self.drop_chance), ("Wave heal", self.wave_heal)]
+    }
+
+    pub fn adjust(&mut self, field: usize, delta: f32) {
+        let v = match field { 0 => &mut self.player_hp, 1 => &mut self.zombie_speed, 2 => &mut self.zombie_hp, 3 => &mut self.spawn_budget, 4 => &mut self.drop_chance, _ => &mut self.wave_heal };
+        let min = if field == 5 { 0.0 } else { 0.1 };
+        *v = ((*v + delta) * 100.0).round() / 100.0;
+        *v = v.clamp(min, 5.0);
+    }
+}
+
+pub fn scaled(v: i32, mult: f32) -> i32 { (v as f32 * mult).round() as i32 }
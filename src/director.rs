@@ -0,0 +1,129 @@
+// ---- AI director: paces a wave around how hard the player is being pushed ----
+// Stress blends recent damage, missing HP, accuracy, kill rate and time since the
+// last hit. The director cycles BuildUp -> Peak -> Relief and turns that into a
+// spawn rate, a harder/easier archetype mix and a drop-chance multiplier.
+// Every phase change (and a periodic snapshot) is logged to stderr for tuning,
+// unless `quiet` (headless bot runs, env.rs).
+// It learns about shots, hits, kills and damage from the gameplay event stream.
+// -------------------------------------------------------------------------------
+
+use crate::events::{Cause, Event, Listener};
+
+const PEAK_STRESS: f32 = 0.65; // build-up ends once stress reaches this...
+const MAX_BUILD_UP: f32 = 40.0; // ...or after this many seconds regardless
+const PEAK_TIME: f32 = 4.0;
+const MIN_RELIEF: f32 = 3.0;
+const MAX_RELIEF: f32 = 9.0;
+const CALM_STRESS: f32 = 0.25; // relief can end early once stress falls below this
+const EMA_RATE: f32 = 0.12; // per-second decay of the rolling shot/hit/kill counters
+const LOG_EVERY: f32 = 10.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Phase { BuildUp, Peak, Relief }
+
+pub struct Director {
+    pub adaptive: bool, // off = fixed, neutral pacing (daily challenge)
+    pub quiet: bool, // no stderr log
+    pub phase: Phase,
+    phase_time: f32,
+    pub stress: f32,
+    recent_damage: f32, // decays ~25%/s
+    shots: f32, hits: f32, kills: f32, // exponentially decayed counts
+    since_hit: f32,
+    hp_frac: f32,
+    clock: f32, log_timer: f32, wave: u32,
+}
+
+impl Director {
+    pub fn new() -> Director {
+        Director { adaptive: true, quiet: false, phase: Phase::BuildUp, phase_time: 0.0, stress: 0.0, recent_damage: 0.0, shots: 0.0, hits: 0.0, kills: 0.0, since_hit: 0.0, hp_frac: 1.0, clock: 0.0, log_timer: LOG_EVERY, wave: 1 }
+    }
+
+    fn start_wave(&mut self, wave: u32) {
+        self.wave = wave;
+        if !self.adaptive { return; }
+        self.set_phase(Phase::BuildUp, "new wave");
+    }
+
+    fn on_damage(&mut self, amount: i32) { self.recent_damage += amount as f32; self.since_hit = 0.0; }
+
+    pub fn update(&mut self, dt: f32, hp: i32, max_hp: i32) {
+        if !self.adaptive { return; }
+        self.clock += dt; self.phase_time += dt; self.since_hit += dt;
+        self.recent_damage *= (1.0 - 0.25 * dt).max(0.0);
+        let decay = (1.0 - EMA_RATE * dt).max(0.0);
+        self.shots *= decay; self.hits *= decay; self.kills *= decay;
+        self.hp_frac = hp.max(0) as f32 / max_hp as f32;
+
+        let dmg = (self.recent_damage / 3.0).min(1.0);
+        let miss = 1.0 - self.accuracy();
+        let calm = (self.since_hit / 20.0).min(1.0);
+        let kill = (self.kill_rate() / 1.5).min(1.0);
+        self.stress = (0.4 * dmg + 0.3 * (1.0 - self.hp_frac) + 0.1 * miss + 0.2 * (1.0 - calm) - 0.15 * kill).clamp(0.0, 1.0);
+
+        match self.phase {
+            Phase::BuildUp if self.stress >= PEAK_STRESS => self.set_phase(Phase::Peak, "stress peaked"),
+            Phase::BuildUp if self.phase_time >= MAX_BUILD_UP => self.set_phase(Phase::Peak, "build-up timed out"),
+            Phase::Peak if self.phase_time >= PEAK_TIME => self.set_phase(Phase::Relief, "peak over"),
+            Phase::Relief if self.phase_time >= MAX_RELIEF => self.set_phase(Phase::BuildUp, "relief over"),
+            Phase::Relief if self.phase_time >= MIN_RELIEF && self.stress < CALM_STRESS => self.set_phase(Phase::BuildUp, "player recovered"),
+            _ => {}
+        }
+        self.log_timer -= dt;
+        if self.log_timer <= 0.0 { self.log("snapshot"); }
+    }
+
+    // rolling hit ratio; assume average aim until there's something to go on
+    fn accuracy(&self) -> f32 { if self.shots < 3.0 { 0.5 } else { (self.hits / self.shots).min(1.0) } }
+    fn kill_rate(&self) -> f32 { self.kills * EMA_RATE }
+
+    // multiplier on how many zombies may be alive at once; zero pauses spawning
+    pub fn spawn_scale(&self) -> f32 {
+        if !self.adaptive { return 1.0; }
+        match self.phase {
+            Phase::BuildUp => 0.75 + 0.5 * (self.phase_time / 25.0).min(1.0) - 0.3 * self.stress,
+            Phase::Peak => 1.25,
+            Phase::Relief => 0.0,
+        }
+    }
+
+    // > 0 skews spawns toward tanks/fast zombies, < 0 toward normals
+    pub fn toughness(&self) -> f32 { if !self.adaptive { 0.0 } else if self.phase == Phase::Relief { -0.5 } else { 0.5 - self.stress } }
+
+    // struggling players and relief periods see more powerups
+    pub fn drop_scale(&self) -> f32 {
+        if !self.adaptive { return 1.0; }
+        match self.phase { Phase::Relief => 1.5, _ => 0.7 + 1.1 * self.stress }
+    }
+
+    fn set_phase(&mut self, phase: Phase, why: &str) {
+        let from = self.phase;
+        self.phase = phase; self.phase_time = 0.0;
+        self.log(&format!("{:?} -> {:?} ({})", from, phase, why));
+    }
+
+    fn log(&mut self, what: &str) {
+        self.log_timer = LOG_EVERY;
+        if self.quiet { return; }
+        eprintln!("[director] t={:.1}s wave {} {}: stress {:.2} dmg {:.1} hp {:.0}% acc {:.2} kills/s {:.2} calm {:.0}s -> spawn x{:.2} tough {:+.2} drops x{:.2}",
+            self.clock, self.wave, what, self.stress, self.recent_damage, self.hp_frac * 100.0, self.accuracy(), self.kill_rate(), self.since_hit,
+            self.spawn_scale(), self.toughness(), self.drop_scale());
+    }
+}
+
+impl Default for Director {
+    fn default() -> Director { Director::new() }
+}
+
+impl Listener for Director {
+    fn on_event(&mut self, e: &Event) {
+        match *e {
+            Event::ShotFired { .. } => self.shots += 1.0,
+            Event::ZombieHit { .. } => self.hits += 1.0,
+            Event::ZombieKilled { cause: Cause::Bullet, .. } => self.kills += 1.0, // bombs say nothing about aim
+            Event::PlayerDamaged { amount, .. } => self.on_damage(amount),
+            Event::WaveStarted { wave, .. } => self.start_wave(wave),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use macroquad::prelude::*;
+
+use crate::arena::{Arena, Cover, SpawnZone, COVER_HP, COVER_SIZE};
+use crate::map;
+
+// ---- Map editor: top-down view of the arena floor ----
+// LMB place (current tool) or select + drag • arrows resize selection (Shift: height)
+// Del remove • 1-4 tool • [ ] arena size • G snap • Ctrl+Z / Ctrl+Y undo / redo
+// Ctrl+S save • P playtest • Esc back to map select (asks first if there are unsaved edits)
+// -------------------------------------------------------
+
+const SNAP: f32 = 0.5;
+const UNDO_LIMIT: usize = 200;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Tool { Cover, Zone, Pickup, Spawn }
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Sel { Cover(usize), Zone(usize), Pickup(usize), Spawn }
+
+pub enum EditorAction { None, Playtest, Exit }
+
+pub struct Editor {
+    pub arena: Arena,
+    pub file: String,
+    tool: Tool,
+    sel: Option<Sel>,
+    drag: Option<(Vec2, Option<Arena>)>, // grab offset, and the pre-drag snapshot until the first move
+    undo: Vec<Arena>, redo: Vec<Arena>,
+    snap: bool,
+    status: String,
+    saved: String, // the map as last loaded or saved, to spot unsaved edits
+}
+
+impl Editor {
+    pub fn new(mut arena: Arena, file: String) -> Editor {
+        arena.seed = None; // once edited it's a hand-authored map
+        let status = format!("Editing {}", file);
+        let saved = map::to_string(&arena);
+        Editor { arena, file, tool: Tool::Cover, sel: None, drag: None, undo: vec![], redo: vec![], snap: true, status, saved }
+    }
+
+    pub fn dirty(&self) -> bool { map::to_string(&self.arena) != self.saved }
+
+    fn camera(&self) -> Camera3D {
+        let fit = (self.arena.half * 2.0 + 4.0).max((self.arena.half * 2.0 + 4.0) * screen_height() / screen_width());
+        Camera3D { position: vec3(0.0, 80.0, 0.0), target: Vec3::ZERO, up: vec3(0.0, 0.0, -1.0), fovy: fit, projection: Projection::Orthographics, ..Default::default() }
+    }
+
+    // mouse ray onto the floor plane
+    fn mouse_floor(&self) -> Vec2 {
+        let inv = self.camera().matrix().inverse();
+        let (mx, my) = mouse_position();
+        let ndc = vec2(mx / screen_width() * 2.0 - 1.0, 1.0 - my / screen_height() * 2.0);
+        let (near, far) = (inv.project_point3(vec3(ndc.x, ndc.y, -1.0)), inv.project_point3(vec3(ndc.x, ndc.y, 1.0)));
+        let t = if (far.y - near.y).abs() > 1e-6 { -near.y / (far.y - near.y) } else { 0.0 };
+        let p = near + (far - near) * t;
+        vec2(p.x, p.z)
+    }
+
+    fn snapped(&self, p: Vec2) -> Vec2 { if self.snap { (p / SNAP).round() * SNAP } else { p } }
+
+    fn checkpoint(&mut self) {
+        self.undo.push(self.arena.clone()); self.redo.clear();
+        if self.undo.len() > UNDO_LIMIT { self.undo.remove(0); }
+    }
+
+    fn pick(&self, p: Vec2) -> Option<Sel> {
+        if (p - self.arena.spawn).length() < 0.8 { return Some(Sel::Spawn); }
+        if let Some(i) = self.arena.pickups.iter().position(|q| (p - *q).length() < 0.6) { return Some(Sel::Pickup(i)); }
+        if let Some(i) = self.arena.covers.iter().rposition(|c| (p - vec2(c.pos.x, c.pos.z)).abs().cmple(vec2(c.size.x, c.size.z) * 0.5).all()) { return Some(Sel::Cover(i)); }
+        self.arena.zones.iter().rposition(|z| (p - z.pos).length() < z.radius).map(Sel::Zone)
+    }
+
+    fn pos_of(&self, sel: Sel) -> Vec2 {
+        match sel {
+            Sel::Cover(i) => vec2(self.arena.covers[i].pos.x, self.arena.covers[i].pos.z),
+            Sel::Zone(i) => self.arena.zones[i].pos,
+            Sel::Pickup(i) => self.arena.pickups[i],
+            Sel::Spawn => self.arena.spawn,
+        }
+    }
+
+    fn set_pos(&mut self, sel: Sel, p: Vec2) {
+        let lim = self.arena.half;
+        let p = p.clamp(Vec2::splat(-lim), Vec2::splat(lim));
+        match sel {
+            Sel::Cover(i) => { let c = &mut self.arena.covers[i]; c.pos.x = p.x; c.pos.z = p.y; }
+            Sel::Zone(i) => self.arena.zones[i].pos = p,
+            Sel::Pickup(i) => self.arena.pickups[i] = p,
+            Sel::Spawn => self.arena.spawn = p,
+        }
+    }
+
+    pub fn update(&mut self) -> EditorAction {
+        let ctrl = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl) || is_key_down(KeyCode::LeftSuper);
+        let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+        let mouse = self.mouse_floor();
+
+        if is_key_pressed(KeyCode::Escape) { return EditorAction::Exit; }
+        if is_key_pressed(KeyCode::P) {
+            match map::parse(&map::to_string(&self.arena)) {
+                Ok(_) => return EditorAction::Playtest,
+                Err(e) => self.status = format!("Can't playtest: {}", e),
+            }
+        }
+        if ctrl && is_key_pressed(KeyCode::S) {
+            self.status = match map::save(map::MAP_DIR, &self.file, &self.arena) {
+                Ok(()) => { self.saved = map::to_string(&self.arena); format!("Saved {}/{}", map::MAP_DIR, self.file) }
+                Err(e) => format!("Not saved: {}", e),
+            };
+        }
+        let redo = ctrl && (is_key_pressed(KeyCode::Y) || (shift && is_key_pressed(KeyCode::Z)));
+        if ctrl && !shift && is_key_pressed(KeyCode::Z) {
+            if let Some(prev) = self.undo.pop() { self.redo.push(std::mem::replace(&mut self.arena, prev)); self.sel = None; }
+        } else if redo {
+            if let Some(next) = self.redo.pop() { self.undo.push(std::mem::replace(&mut self.arena, next)); self.sel = None; }
+        }
+
+        // tools & toggles
+        for (key, tool) in [(KeyCode::Key1, Tool::Cover), (KeyCode::Key2, Tool::Zone), (KeyCode::Key3, Tool::Pickup), (KeyCode::Key4, Tool::Spawn)] {
+            if is_key_pressed(key) { self.tool = tool; }
+        }
+        if is_key_pressed(KeyCode::G) { self.snap = !self.snap; }
+        if is_key_pressed(KeyCode::LeftBracket) || is_key_pressed(KeyCode::RightBracket) {
+            self.checkpoint();
+            let step = if is_key_pressed(KeyCode::RightBracket) { 1.0 } else { -1.0 };
+            self.arena.half = (self.arena.half + step).clamp(8.0, 64.0);
+        }
+
+        // select / place / drag
+        if is_mouse_button_pressed(MouseButton::Left) {
+            match self.pick(mouse) {
+                Some(sel) => { self.sel = Some(sel); self.drag = Some((self.pos_of(sel) - mouse, Some(self.arena.clone()))); }
+                None => {
+                    self.checkpoint();
+                    let p = self.snapped(mouse);
+                    self.sel = Some(match self.tool {
+                        Tool::Cover => { self.arena.covers.push(Cover::new(p.x, p.y, COVER_SIZE, COVER_HP)); Sel::Cover(self.arena.covers.len() - 1) }
+                        Tool::Zone => { self.arena.zones.push(SpawnZone { pos: p, radius: 2.5 }); Sel::Zone(self.arena.zones.len() - 1) }
+                        Tool::Pickup => { self.arena.pickups.push(p); Sel::Pickup(self.arena.pickups.len() - 1) }
+                        Tool::Spawn => { self.arena.spawn = p; Sel::Spawn }
+                    });
+                }
+            }
+        }
+        if !is_mouse_button_down(MouseButton::Left) { self.drag = None; }
+        if let (Some(sel), Some((offset, _))) = (self.sel, self.drag.as_ref()) {
+            let target = self.snapped(mouse + *offset);
+            if target != self.pos_of(sel) {
+                if let Some(before) = self.drag.as_mut().and_then(|d| d.1.take()) { self.undo.push(before); self.redo.clear(); }
+                self.set_pos(sel, target);
+            }
+        }
+
+        // resize / delete the selection
+        if let Some(sel) = self.sel {
+            let dx = if is_key_pressed(KeyCode::Right) { SNAP } else if is_key_pressed(KeyCode::Left) { -SNAP } else { 0.0 };
+            let dz = if is_key_pressed(KeyCode::Up) { SNAP } else if is_key_pressed(KeyCode::Down) { -SNAP } else { 0.0 };
+            if dx != 0.0 || dz != 0.0 {
+                self.checkpoint();
+                match sel {
+                    Sel::Cover(i) => {
+                        let c = &mut self.arena.covers[i];
+                        if shift { c.size.y = (c.size.y + dz).max(SNAP); c.pos.y = c.size.y * 0.5; }
+                        else { c.size.x = (c.size.x + dx).max(SNAP); c.size.z = (c.size.z + dz).max(SNAP); }
+                    }
+                    Sel::Zone(i) => { let z = &mut self.arena.zones[i]; z.radius = (z.radius + dx + dz).max(SNAP); }
+                    Sel::Pickup(_) | Sel::Spawn => {}
+                }
+            }
+            if is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace) {
+                self.checkpoint();
+                match sel {
+                    Sel::Cover(i) => { self.arena.covers.remove(i); }
+                    Sel::Zone(i) => { self.arena.zones.remove(i); }
+                    Sel::Pickup(i) => { self.arena.pickups.remove(i); }
+                    Sel::Spawn => {} // there is always a spawn
+                }
+                self.sel = None; self.drag = None;
+            }
+        }
+        EditorAction::None
+    }
+
+    pub fn draw(&self) {
+        set_camera(&self.camera());
+        crate::draw_arena(&self.arena);
+        for z in &self.arena.zones { draw_cylinder(vec3(z.pos.x, 0.0, z.pos.y), z.radius, z.radius, 0.05, None, Color::from_rgba(200, 60, 60, 120)); }
+        for p in &self.arena.pickups { draw_sphere(vec3(p.x, 0.5, p.y), 0.35, None, GOLD); }
+        draw_cube(vec3(self.arena.spawn.x, 0.5, self.arena.spawn.y), vec3(1.0, 1.0, 1.0), None, SKYBLUE);
+        if let Some(sel) = self.sel {
+            let (pos, size) = match sel {
+                Sel::Cover(i) => (self.arena.covers[i].pos, self.arena.covers[i].size),
+                Sel::Zone(i) => { let z = self.arena.zones[i]; (vec3(z.pos.x, 0.1, z.pos.y), vec3(z.radius * 2.0, 0.2, z.radius * 2.0)) }
+                Sel::Pickup(i) => (vec3(self.arena.pickups[i].x, 0.5, self.arena.pickups[i].y), Vec3::splat(0.9)),
+                Sel::Spawn => (vec3(self.arena.spawn.x, 0.5, self.arena.spawn.y), Vec3::splat(1.2)),
+            };
+            draw_cube_wires(pos, size + Vec3::splat(0.1), YELLOW);
+        }
+        set_default_camera();
+
+        let tool = match self.tool { Tool::Cover => "Cover", Tool::Zone => "Spawn zone", Tool::Pickup => "Pickup", Tool::Spawn => "Player spawn" };
+        let head = format!("EDITOR  {}  •  Tool: {}  •  Size: {}  •  Snap: {}", self.file, tool, self.arena.half * 2.0, if self.snap { "on" } else { "off" });
+        draw_text(&head, 16.0, 28.0, 24.0, WHITE);
+        draw_text(&self.status, 16.0, 52.0, 20.0, GRAY);
+        let info = "LMB place/drag • Arrows resize (Shift height) • Del remove • 1-4 tool • [ ] size • G snap • Ctrl+Z/Y undo/redo • Ctrl+S save • P playtest • Esc back";
+        let t = measure_text(info, None, 18, 1.0);
+        draw_text(info, screen_width()*0.5 - t.width*0.5, screen_height() - 18.0, 18.0, GRAY);
+    }
+}
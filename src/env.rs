@@ -0,0 +1,177 @@
+use macroquad::prelude::*;
+
+use crate::arena::Arena;
+use crate::coop::Coop;
+use crate::difficulty::{Difficulty, Preset};
+use crate::events;
+use crate::input::PlayerInput;
+use crate::mode::{Mode, Outcome};
+use crate::{intermission, step, Drop, Game, Step, ZKind};
+
+// ---- Bot environment: the simulation behind a gym-style interface ----
+// For training and evaluating bots. Env::reset(seed) starts a solo run and returns
+// the first observation; Env::step(input) plays `repeat` frames of `dt` with that
+// input and returns (observation, reward, done). Wave breaks are played through
+// inside step(), since the player can't act during them. No window is needed, so
+// it runs as fast as the simulation does (tens of thousands of frames a second).
+// The action is the same PlayerInput a human produces; action(i) maps a discrete
+// index in 0..ACTIONS onto one (9 moves x hold fire / fire in 8 directions).
+// Observation::features is always FEATURES long, every value roughly in -1..1:
+//   player   x, z (over the arena's half size), hp, shot cooldown, rapid, slow left
+//   run      wave, combo, zombies alive, zombies still to spawn this wave
+//   zombies  the NEAREST_ZOMBIES nearest: present, dx, dz, distance, normal/fast/tank, hp, rising
+//   drops    the NEAREST_DROPS nearest: present, dx, dz, distance, heal/bomb/rapid/slow
+// (absent entries are all zeros). With Config::grid = n > 0, Observation::grid is
+// also filled: GRID_CHANNELS n x n top-down planes over the whole arena (walls and
+// cover, zombies, drops, the player), 1.0 where occupied, laid out [channel][row][col]
+// with row 0 at -z. Reward: points scored / 10, minus HURT per HP lost, minus DEATH
+// when the run is lost; done once the run is over or Config::max_secs have passed.
+// -----------------------------------------------
+
+pub const NEAREST_ZOMBIES: usize = 8;
+pub const NEAREST_DROPS: usize = 4;
+const PLAYER_FEATURES: usize = 6;
+const RUN_FEATURES: usize = 4;
+const ZOMBIE_FEATURES: usize = 9;
+const DROP_FEATURES: usize = 8;
+pub const FEATURES: usize = PLAYER_FEATURES + RUN_FEATURES + NEAREST_ZOMBIES * ZOMBIE_FEATURES + NEAREST_DROPS * DROP_FEATURES;
+pub const GRID_CHANNELS: usize = 4;
+pub const ACTIONS: usize = 81;
+
+const HURT: f32 = 2.0;
+const DEATH: f32 = 10.0;
+
+#[derive(Clone)]
+pub struct Config {
+    pub mode: Mode, pub diff: Difficulty,
+    pub map: Option<Arena>, // None: generate one from each reset's seed
+    pub dt: f32, pub repeat: u32, // frame length and frames per step
+    pub grid: usize, // occupancy grid cells per side; 0 for none
+    pub max_secs: f32,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config { mode: Mode::Waves, diff: Difficulty::preset(Preset::Normal), map: None, dt: 1.0 / 60.0, repeat: 4, grid: 0, max_secs: 600.0 }
+    }
+}
+
+pub struct Observation { pub features: [f32; FEATURES], pub grid: Option<Vec<f32>> }
+
+pub struct Env { pub cfg: Config, pub game: Game, pub steps: u64 }
+
+impl Env {
+    // an env is ready to step straight away (on seed 0); reset() starts a fresh run
+    pub fn new(cfg: Config) -> Env {
+        let game = new_run(&cfg, 0);
+        let mut env = Env { cfg, game, steps: 0 };
+        env.dispatch();
+        env
+    }
+
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.game = new_run(&self.cfg, seed);
+        self.steps = 0;
+        self.dispatch();
+        self.observe()
+    }
+
+    pub fn step(&mut self, input: PlayerInput) -> (Observation, f32, bool) {
+        let (score, hp) = (self.game.score, self.game.players[0].hp);
+        let mut over = self.game.outcome.is_some();
+        for _ in 0..self.cfg.repeat.max(1) {
+            if over { break; }
+            match step(&mut self.game, self.cfg.dt, std::slice::from_ref(&input)) {
+                Step::Over => over = true,
+                Step::WaveCleared => while !intermission(&mut self.game, self.cfg.dt) {},
+                Step::Playing => {}
+            }
+            self.dispatch();
+        }
+        self.steps += 1;
+        let g = &self.game;
+        let lost = matches!(g.outcome, Some(Outcome::Died | Outcome::Failed));
+        let reward = (g.score - score) as f32 / 10.0 - (hp - g.players[0].hp).max(0) as f32 * HURT - if lost { DEATH } else { 0.0 };
+        (self.observe(), reward, over || g.elapsed >= self.cfg.max_secs)
+    }
+
+    // stats and the director still hear about everything, as in a real run
+    fn dispatch(&mut self) {
+        let g = &mut self.game;
+        let batch = std::mem::take(&mut g.events);
+        events::dispatch(&batch, &mut [&mut g.stats, &mut g.director]);
+    }
+
+    pub fn observe(&self) -> Observation {
+        let g = &self.game;
+        let p = &g.players[0];
+        let half = g.arena.half.max(1.0);
+        let mut f = [0.0; FEATURES];
+        f[..PLAYER_FEATURES].copy_from_slice(&[
+            p.pos.x / half, p.pos.z / half, p.hp.max(0) as f32 / g.max_hp.max(1) as f32, p.cd.max(0.0) / 0.2, (p.rapid_left / 10.0).min(1.0), (p.slow_left / 10.0).min(1.0),
+        ]);
+        let run = [(g.wave as f32 / 20.0).min(1.0), g.combo / 4.0, (g.zombies.len() as f32 / 50.0).min(1.0), (g.spawn_budget_left as f32 / 120.0).min(1.0)];
+        f[PLAYER_FEATURES..PLAYER_FEATURES + RUN_FEATURES].copy_from_slice(&run);
+
+        let rel = |at: Vec3| { let d = vec2(at.x - p.pos.x, at.z - p.pos.z) / (half * 2.0); (d, d.length()) };
+        let mut zombies: Vec<_> = g.zombies.iter().map(|z| (z, rel(z.pos))).collect();
+        zombies.sort_by(|a, b| a.1 .1.total_cmp(&b.1 .1));
+        let mut at = PLAYER_FEATURES + RUN_FEATURES;
+        for (z, (d, len)) in zombies.into_iter().take(NEAREST_ZOMBIES) {
+            let kind = |k: ZKind| f32::from(z.kind == k);
+            f[at..at + ZOMBIE_FEATURES].copy_from_slice(&[
+                1.0, d.x, d.y, len, kind(ZKind::Normal), kind(ZKind::Fast), kind(ZKind::Tank), z.hp as f32 / 3.0, f32::from(z.rise > 0.0),
+            ]);
+            at += ZOMBIE_FEATURES;
+        }
+        let mut drops: Vec<_> = g.drops.iter().map(|d| (d.kind, rel(d.pos))).collect();
+        drops.sort_by(|a, b| a.1 .1.total_cmp(&b.1 .1));
+        at = PLAYER_FEATURES + RUN_FEATURES + NEAREST_ZOMBIES * ZOMBIE_FEATURES;
+        for (kind, (d, len)) in drops.into_iter().take(NEAREST_DROPS) {
+            let is = |k: Drop| f32::from(kind == k);
+            f[at..at + DROP_FEATURES].copy_from_slice(&[1.0, d.x, d.y, len, is(Drop::Heal), is(Drop::Bomb), is(Drop::Rapid), is(Drop::Slow)]);
+            at += DROP_FEATURES;
+        }
+        Observation { features: f, grid: (self.cfg.grid > 0).then(|| self.grid(self.cfg.grid)) }
+    }
+
+    fn grid(&self, n: usize) -> Vec<f32> {
+        let g = &self.game;
+        let mut out = vec![0.0; GRID_CHANNELS * n * n];
+        let cell = g.arena.half * 2.0 / n as f32;
+        let index = |x: f32, z: f32| {
+            let c = |v: f32| ((v + g.arena.half) / cell).floor().clamp(0.0, n as f32 - 1.0) as usize;
+            c(z) * n + c(x)
+        };
+        // boxes cover every cell they overlap
+        let mut fill = |ch: usize, pos: Vec3, size: Vec3| {
+            let (lo, hi) = (index(pos.x - size.x * 0.5, pos.z - size.z * 0.5), index(pos.x + size.x * 0.5, pos.z + size.z * 0.5));
+            for row in lo / n..=hi / n { for col in lo % n..=hi % n { out[ch * n * n + row * n + col] = 1.0; } }
+        };
+        for w in &g.arena.walls { fill(0, w.pos, w.size); }
+        for c in &g.arena.covers { fill(0, c.pos, c.size); }
+        for z in &g.zombies { fill(1, z.pos, Vec3::ZERO); }
+        for d in &g.drops { fill(2, d.pos, Vec3::ZERO); }
+        fill(3, g.players[0].pos, Vec3::ZERO);
+        out
+    }
+}
+
+fn new_run(cfg: &Config, seed: u64) -> Game {
+    let arena = cfg.map.clone().unwrap_or_else(|| Arena::generate(seed));
+    let mut g = Game::new(arena, cfg.mode, cfg.diff, Coop::Solo, seed);
+    g.director.quiet = true; // thousands of runs would bury stderr
+    g
+}
+
+// discrete action i: i / 9 picks the move (still, then N, NE, E, ... clockwise),
+// i % 9 holds fire (0) or fires that way (1..=8, N first); sprinting is left out
+pub fn action(i: usize) -> PlayerInput {
+    let dir = |k: usize| { let a = (k - 1) as f32 * std::f32::consts::FRAC_PI_4; vec2(a.sin(), -a.cos()) };
+    let (m, a) = ((i / 9) % 9, i % 9);
+    PlayerInput {
+        move_dir: if m == 0 { Vec2::ZERO } else { dir(m) },
+        fire: a > 0, aim: (a > 0).then(|| dir(a)),
+        ..Default::default()
+    }
+}
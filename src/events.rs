@@ -0,0 +1,61 @@
+use macroquad::prelude::*;
+
+use crate::mode::Outcome;
+use crate::{Drop, ZKind};
+
+// ---- Gameplay events ----
+// The simulation only records what happened by pushing onto Game::events. Once a
+// frame main.rs hands the batch to every Listener (stats, achievements, the
+// director, camera/effects, the event log), so the collision code doesn't know
+// who is watching. `--log-events` prints every event to stderr.
+// -------------------------
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Cause { Bullet, Bomb }
+
+#[derive(Clone, Copy)]
+pub enum Event {
+    ShotFired { pos: Vec3 },
+    ZombieSpawned { id: u32, kind: ZKind, pos: Vec3 }, // id: Zombie::id, unique within a run; it rises for SPAWN_TELEGRAPH first
+    ZombieHit { kind: ZKind, pos: Vec3 },
+    // combo = multiplier after this kill, chain = kills in the current combo so far
+    ZombieKilled { id: u32, kind: ZKind, pos: Vec3, cause: Cause, combo: f32, chain: u32 },
+    GeneratorHit { id: u32, kind: ZKind, pos: Vec3, hp: f32 }, // the zombie is spent on it; hp = generator's left
+    PlayerDamaged { zombie: u32, source: ZKind, amount: i32, hp: i32 }, // zombie: the id of the one spent on the hit
+    PowerupPicked { kind: Drop, pos: Vec3 },
+    PlayerDowned { player: usize, pos: Vec3 }, // co-op: out until revived (alone, that's the run over)
+    PlayerRevived { player: usize, pos: Vec3 },
+    WaveStarted { wave: u32, at: f32 }, // `at` = seconds into the run
+    WaveCleared { wave: u32, at: f32 },
+    GameOver { outcome: Outcome, score: u32, wave: u32, secs: f32 },
+}
+
+pub trait Listener { fn on_event(&mut self, e: &Event); }
+
+pub fn dispatch(events: &[Event], listeners: &mut [&mut dyn Listener]) {
+    for e in events { for l in listeners.iter_mut() { l.on_event(e); } }
+}
+
+pub struct Logger { pub enabled: bool }
+
+impl Listener for Logger {
+    fn on_event(&mut self, e: &Event) {
+        if !self.enabled { return; }
+        let at = |p: Vec3| format!("({:.1}, {:.1})", p.x, p.z);
+        let line = match *e {
+            Event::ShotFired { pos } => format!("shot from {}", at(pos)),
+            Event::ZombieSpawned { id, kind, pos } => format!("{:?} #{} spawned at {}", kind, id, at(pos)),
+            Event::ZombieHit { kind, pos } => format!("hit {:?} at {}", kind, at(pos)),
+            Event::ZombieKilled { id, kind, pos, cause, combo, chain } => format!("killed {:?} #{} at {} by {:?} (combo x{:.2}, chain {})", kind, id, at(pos), cause, combo, chain),
+            Event::GeneratorHit { id, kind, pos, hp } => format!("{:?} #{} smashed into the generator at {}, {:.0} hp left", kind, id, at(pos), hp),
+            Event::PlayerDamaged { zombie, source, amount, hp } => format!("player took {} from {:?} #{}, {} hp left", amount, source, zombie, hp),
+            Event::PowerupPicked { kind, pos } => format!("picked {:?} at {}", kind, at(pos)),
+            Event::PlayerDowned { player, pos } => format!("player {} down at {}", player + 1, at(pos)),
+            Event::PlayerRevived { player, pos } => format!("player {} back up at {}", player + 1, at(pos)),
+            Event::WaveStarted { wave, at } => format!("wave {} started at {:.1}s", wave, at),
+            Event::WaveCleared { wave, at } => format!("wave {} cleared at {:.1}s", wave, at),
+            Event::GameOver { outcome, score, wave, secs } => format!("game over: {:?}, score {}, wave {}, {:.1}s", outcome, score, wave, secs),
+        };
+        eprintln!("[event] {}", line);
+    }
+}
@@ -0,0 +1,67 @@
+use macroquad::prelude::*;
+
+use crate::events::{Cause, Event, Listener};
+use crate::{Drop, BOMB_RADIUS};
+
+// ---- Presentation effects driven by gameplay events ----
+// Camera shake from shots and hits, a puff where a zombie dies, the bomb's blast
+// ring and a red screen flash when the player is hurt. Purely cosmetic: nothing
+// here feeds back into the simulation.
+// --------------------------------------------------------
+
+struct Puff { pos: Vec3, radius: f32, ttl: f32, life: f32, col: Color }
+
+#[derive(Default)]
+pub struct Fx {
+    pub intensity: f32, // screen shake setting, 1 = full
+    pub shake: f32,
+    flash: f32,
+    puffs: Vec<Puff>,
+}
+
+impl Listener for Fx {
+    fn on_event(&mut self, e: &Event) {
+        let puff = |pos: Vec3, radius: f32, life: f32, col: Color| Some(Puff { pos, radius, ttl: life, life, col });
+        let new = match *e {
+            Event::ShotFired { .. } => { self.shake = (self.shake + 0.25).min(1.0); None }
+            Event::ZombieKilled { pos, cause: Cause::Bullet, .. } => puff(pos, 1.0, 0.35, Color::new(0.4, 0.9, 0.4, 0.6)),
+            Event::ZombieKilled { pos, cause: Cause::Bomb, .. } => puff(pos, 1.4, 0.5, Color::new(1.0, 0.6, 0.2, 0.6)),
+            Event::PowerupPicked { kind: Drop::Bomb, pos } => puff(pos, BOMB_RADIUS, 0.45, Color::new(1.0, 0.5, 0.1, 0.45)),
+            Event::GeneratorHit { pos, .. } => puff(pos, 1.2, 0.4, Color::new(1.0, 0.85, 0.3, 0.6)),
+            Event::PlayerDamaged { .. } => { self.shake = (self.shake + 0.6).min(1.4); self.flash = 0.35; None }
+            _ => None,
+        };
+        self.puffs.extend(new);
+    }
+}
+
+impl Fx {
+    pub fn new() -> Fx { Fx { intensity: 1.0, ..Default::default() } }
+
+    pub fn update(&mut self, dt: f32) {
+        self.shake = (self.shake - dt * 4.0).max(0.0);
+        self.flash = (self.flash - dt).max(0.0);
+        for p in &mut self.puffs { p.ttl -= dt; }
+        self.puffs.retain(|p| p.ttl > 0.0);
+    }
+
+    // random camera offset; the global rand is fine here, it's not part of the sim
+    pub fn jitter(&self) -> Vec3 {
+        let s = self.shake * self.intensity;
+        vec3(rand::gen_range(-1.0, 1.0) * 0.15 * s, rand::gen_range(-1.0, 1.0) * 0.10 * s, rand::gen_range(-1.0, 1.0) * 0.2 * s)
+    }
+
+    // in the world camera
+    pub fn draw(&self) {
+        for p in &self.puffs {
+            let t = 1.0 - p.ttl / p.life; // 0 -> 1 over its life
+            let r = p.radius * (0.3 + 0.7 * t);
+            draw_cylinder(vec3(p.pos.x, 0.05, p.pos.z), r, r, 0.05 + 0.6 * (1.0 - t), None, Color { a: p.col.a * (1.0 - t), ..p.col });
+        }
+    }
+
+    // screen space, over everything
+    pub fn draw_overlay(&self) {
+        if self.flash > 0.0 { draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.8, 0.0, 0.0, self.flash * 0.5)); }
+    }
+}
@@ -0,0 +1,144 @@
+use std::cell::RefCell;
+use std::io::Read;
+
+use macroquad::prelude::*;
+
+// ---- Gamepads: Linux joystick devices (/dev/input/js*) read directly ----
+// macroquad has no gamepad API, so on Linux we read the kernel joystick interface
+// ourselves (8-byte events, opened non-blocking). The layout assumed is the Xbox
+// one the xpad/xone drivers report, which most pads also use: axes LX LY LT RX RY
+// RT dpadX dpadY, buttons A B X Y LB RB Back Start Guide L3 R3. Devices are
+// rescanned every second, so pads can come and go mid-game. Like macroquad's
+// keyboard functions this is global, polled state: call update() once a frame.
+// Pads are numbered 0.. in device order; the *_on functions ask about one of them.
+// Elsewhere there are no pads (SUPPORTED is false, which update() logs once) and
+// every query says "nothing pressed"; pads laid out differently will map oddly.
+// -------------------------------------------------------------------------
+
+pub const SUPPORTED: bool = cfg!(target_os = "linux");
+const MAX_PADS: usize = 4;
+const RESCAN_EVERY: f64 = 1.0;
+const DEADZONE: f32 = 0.2;
+const TRIGGER_DOWN: f32 = 0.5; // trigger travel (0..1) that counts as pressed
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Button { A, B, X, Y, LB, RB, LT, RT, Back, Start, L3, R3, Up, Down, Left, Right }
+
+pub const BUTTONS: [Button; 16] = {
+    use Button::*;
+    [A, B, X, Y, LB, RB, LT, RT, Back, Start, L3, R3, Up, Down, Left, Right]
+};
+
+struct Pad {
+    n: usize, // the N in /dev/input/jsN
+    name: String,
+    file: std::fs::File,
+    axes: [f32; 8],
+    buttons: [bool; 11],
+    down: Vec<Button>, prev: Vec<Button>, // resolved buttons this frame / last frame
+}
+
+#[derive(Default)]
+struct State {
+    pads: Vec<Pad>, // sorted by device number
+    last_scan: Option<f64>,
+}
+
+thread_local! { static STATE: RefCell<State> = RefCell::new(State::default()); }
+
+impl Pad {
+    fn button(&self, b: Button) -> bool {
+        let axis = |i: usize| self.axes[i];
+        match b {
+            Button::LT => (axis(2) + 1.0) * 0.5 > TRIGGER_DOWN,
+            Button::RT => (axis(5) + 1.0) * 0.5 > TRIGGER_DOWN,
+            Button::Up => axis(7) < -0.5,
+            Button::Down => axis(7) > 0.5,
+            Button::Left => axis(6) < -0.5,
+            Button::Right => axis(6) > 0.5,
+            b => {
+                let i = match b { Button::A => 0, Button::B => 1, Button::X => 2, Button::Y => 3, Button::LB => 4, Button::RB => 5, Button::Back => 6, Button::Start => 7, Button::L3 => 9, _ => 10 };
+                self.buttons[i]
+            }
+        }
+    }
+
+    // drain pending events; false once the device has gone away
+    fn poll(&mut self) -> bool {
+        let mut ev = [0u8; 8];
+        loop {
+            match self.file.read(&mut ev) {
+                Ok(8) => {
+                    let value = i16::from_ne_bytes([ev[4], ev[5]]);
+                    let (kind, number) = (ev[6] & !0x80, ev[7] as usize); // 0x80 marks the initial-state burst
+                    match kind {
+                        1 if number < self.buttons.len() => self.buttons[number] = value != 0,
+                        2 if number < self.axes.len() => self.axes[number] = value as f32 / 32767.0,
+                        _ => {}
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return true,
+                _ => return false,
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn open(n: usize) -> Option<Pad> {
+    use std::os::unix::fs::OpenOptionsExt;
+    const O_NONBLOCK: i32 = 0o4000;
+    let file = std::fs::OpenOptions::new().read(true).custom_flags(O_NONBLOCK).open(format!("/dev/input/js{}", n)).ok()?;
+    let name = std::fs::read_to_string(format!("/sys/class/input/js{}/device/name", n)).map(|s| s.trim().to_owned()).unwrap_or_else(|_| format!("Joystick {}", n));
+    // triggers rest at -1; until the driver reports them, don't read them as half pressed
+    let mut axes = [0.0; 8];
+    axes[2] = -1.0; axes[5] = -1.0;
+    Some(Pad { n, name, file, axes, buttons: [false; 11], down: vec![], prev: vec![] })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open(_n: usize) -> Option<Pad> { None }
+
+pub fn update() {
+    STATE.with(|s| {
+        let s = &mut *s.borrow_mut();
+        let now = get_time();
+        if s.last_scan.is_none() && !SUPPORTED { eprintln!("controllers aren't supported on this platform (Linux only); co-op player 2 can join online instead"); }
+        if s.last_scan.is_none_or(|t| now - t >= RESCAN_EVERY) {
+            s.last_scan = Some(now);
+            for n in 0..MAX_PADS {
+                if s.pads.iter().any(|p| p.n == n) { continue; }
+                if let Some(p) = open(n) { eprintln!("controller connected: {}", p.name); s.pads.push(p); }
+            }
+            s.pads.sort_by_key(|p| p.n);
+        }
+        s.pads.retain_mut(|p| { let alive = p.poll(); if !alive { eprintln!("controller disconnected: {}", p.name); } alive });
+        for p in &mut s.pads {
+            p.prev = std::mem::take(&mut p.down);
+            p.down = BUTTONS.into_iter().filter(|b| p.button(*b)).collect();
+        }
+    });
+}
+
+fn with_pad<T: Default>(i: usize, f: impl FnOnce(&Pad) -> T) -> T { STATE.with(|s| s.borrow().pads.get(i).map(f).unwrap_or_default()) }
+
+pub fn count() -> usize { STATE.with(|s| s.borrow().pads.len()) }
+pub fn down_on(i: usize, b: Button) -> bool { with_pad(i, |p| p.down.contains(&b)) }
+pub fn pressed_on(i: usize, b: Button) -> bool { with_pad(i, |p| p.down.contains(&b) && !p.prev.contains(&b)) }
+
+// on any pad (menus)
+pub fn is_pressed(b: Button) -> bool { (0..count()).any(|i| pressed_on(i, b)) }
+
+// names of the connected pads, in order
+pub fn connected() -> Vec<String> { STATE.with(|s| s.borrow().pads.iter().map(|p| p.name.clone()).collect()) }
+
+// stick position with the deadzone cut out and the rest rescaled to 0..1, so a
+// half-pushed stick really is half speed; +y is down/towards the camera
+fn stick(i: usize, x: usize, y: usize) -> Vec2 {
+    let v = with_pad(i, |p| vec2(p.axes[x], p.axes[y]));
+    let len = v.length();
+    if len <= DEADZONE { Vec2::ZERO } else { v / len * ((len - DEADZONE) / (1.0 - DEADZONE)).min(1.0) }
+}
+
+pub fn left_stick_on(i: usize) -> Vec2 { stick(i, 0, 1) }
+pub fn right_stick_on(i: usize) -> Vec2 { stick(i, 3, 4) }
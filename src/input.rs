@@ -0,0 +1,215 @@
+use macroquad::prelude::*;
+
+use crate::gamepad::{self, Button, BUTTONS};
+use crate::storage;
+
+// ---- Input: named actions mapped to keys / mouse buttons / pad buttons ----
+// Gameplay code asks about actions (MoveUp, Fire, ...), never about keys, so any
+// action can be rebound. Each action takes up to MAX_BINDINGS bindings; the
+// controls screen (Settings -> Controls) rebinds them and flags a binding used by
+// two actions that are live at the same time. Bindings persist in
+// <data dir>/bindings.txt as `<action> <binding>...` lines, e.g. `fire Mouse:Left Pad:RT`.
+// Sticks aren't bindable: the left one always moves (analog speed), the right one
+// aims, and firing from the pad without aiming gets aim assist (gamepad.rs).
+// Menus keep their fixed arrows/Enter/Esc keys and d-pad/A/B (ui.rs) so they
+// can't be locked out. In co-op each player reads only their own devices (Source).
+// There's no reload mechanic, so no Reload action.
+// --------------------------------------------------------------
+
+pub const MAX_BINDINGS: usize = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action { MoveUp, MoveDown, MoveLeft, MoveRight, Sprint, Fire, Pause, Restart, RetryArena }
+
+pub const ACTIONS: [Action; 9] = [
+    Action::MoveUp, Action::MoveDown, Action::MoveLeft, Action::MoveRight, Action::Sprint, Action::Fire,
+    Action::Pause, Action::Restart, Action::RetryArena,
+];
+
+// actions are only live in one of these, so e.g. R can restart and also move elsewhere
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Context { Playing, Results }
+
+impl Action {
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move up", Action::MoveDown => "Move down", Action::MoveLeft => "Move left", Action::MoveRight => "Move right",
+            Action::Sprint => "Sprint", Action::Fire => "Fire", Action::Pause => "Pause",
+            Action::Restart => "Restart (results)", Action::RetryArena => "Retry same arena (results)",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Action::MoveUp => "move_up", Action::MoveDown => "move_down", Action::MoveLeft => "move_left", Action::MoveRight => "move_right",
+            Action::Sprint => "sprint", Action::Fire => "fire", Action::Pause => "pause", Action::Restart => "restart", Action::RetryArena => "retry_arena",
+        }
+    }
+
+    fn context(self) -> Context { if matches!(self, Action::Restart | Action::RetryArena) { Context::Results } else { Context::Playing } }
+}
+
+// which devices drive a player: everything when playing alone; in co-op one
+// player gets a single pad and the other keeps keyboard/mouse and any other pads
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Source { All, Pad(usize), AllBut(usize) }
+
+impl Source {
+    fn keys(self) -> bool { !matches!(self, Source::Pad(_)) }
+    fn pads(self) -> impl Iterator<Item = usize> {
+        (0..gamepad::count()).filter(move |i| match self { Source::All => true, Source::Pad(p) => p == *i, Source::AllBut(p) => p != *i })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Binding { Key(KeyCode), Mouse(MouseButton), Pad(Button) }
+
+// every key we accept for a binding; names in the file are their Debug names
+const KEYS: [KeyCode; 78] = {
+    use KeyCode::*;
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+        Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9,
+        Space, Apostrophe, Comma, Minus, Period, Slash, Semicolon, Equal, LeftBracket, Backslash, RightBracket, GraveAccent,
+        Escape, Enter, Tab, Backspace, Insert, Delete, Right, Left, Down, Up, PageUp, PageDown, Home, End,
+        Kp0, Kp1, Kp2, Kp3, Kp4, Kp5, Kp6, Kp7, Kp8, Kp9,
+        LeftShift, LeftControl, LeftAlt, RightShift, RightControl, RightAlt,
+    ]
+};
+const MOUSE: [MouseButton; 3] = [MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+
+impl Binding {
+    fn parse(s: &str) -> Option<Binding> {
+        if let Some(m) = s.strip_prefix("Mouse:") { return MOUSE.into_iter().find(|b| format!("{:?}", b) == m).map(Binding::Mouse); }
+        if let Some(p) = s.strip_prefix("Pad:") { return BUTTONS.into_iter().find(|b| format!("{:?}", b) == p).map(Binding::Pad); }
+        KEYS.into_iter().find(|k| format!("{:?}", k) == s).map(Binding::Key)
+    }
+
+    fn file_name(self) -> String {
+        match self { Binding::Key(k) => format!("{:?}", k), Binding::Mouse(b) => format!("Mouse:{:?}", b), Binding::Pad(b) => format!("Pad:{:?}", b) }
+    }
+
+    // for the HUD and the controls screen
+    pub fn label(self) -> String {
+        match self {
+            Binding::Key(k) => { let n = format!("{:?}", k); n.strip_prefix("Key").filter(|d| d.len() == 1).map_or(n.clone(), str::to_owned) }
+            Binding::Mouse(MouseButton::Left) => "LMB".to_owned(),
+            Binding::Mouse(MouseButton::Right) => "RMB".to_owned(),
+            Binding::Mouse(b) => format!("Mouse {:?}", b),
+            Binding::Pad(b) => format!("Pad {:?}", b),
+        }
+    }
+
+    fn down(self, src: Source) -> bool {
+        match self {
+            Binding::Key(k) => src.keys() && is_key_down(k),
+            Binding::Mouse(b) => src.keys() && is_mouse_button_down(b),
+            Binding::Pad(b) => src.pads().any(|i| gamepad::down_on(i, b)),
+        }
+    }
+    fn pressed(self) -> bool {
+        match self { Binding::Key(k) => is_key_pressed(k), Binding::Mouse(b) => is_mouse_button_pressed(b), Binding::Pad(b) => gamepad::is_pressed(b) }
+    }
+
+    // whatever was pressed this frame, for the rebinding prompt
+    pub fn captured() -> Option<Binding> {
+        KEYS.into_iter().find(|k| *k != KeyCode::Escape && is_key_pressed(*k)).map(Binding::Key)
+            .or_else(|| MOUSE.into_iter().find(|b| is_mouse_button_pressed(*b)).map(Binding::Mouse))
+            .or_else(|| BUTTONS.into_iter().find(|b| gamepad::is_pressed(*b)).map(Binding::Pad))
+    }
+}
+
+// what the player asked for this frame, already resolved from the bindings.
+// move_dir is at most unit length (shorter for a half-pushed stick); aim is the
+// right stick's direction when it's pushed; assist asks the sim to aim for us
+#[derive(Clone, Copy, Default)]
+pub struct PlayerInput { pub move_dir: Vec2, pub sprint: bool, pub fire: bool, pub aim: Option<Vec2>, pub assist: bool }
+
+#[derive(Clone)]
+pub struct Bindings { map: Vec<Vec<Binding>> } // indexed like ACTIONS
+
+fn index(a: Action) -> usize { ACTIONS.iter().position(|x| *x == a).unwrap_or(0) }
+
+impl Default for Bindings {
+    fn default() -> Bindings {
+        use Binding::{Key, Mouse, Pad};
+        let map = ACTIONS.iter().map(|a| match a {
+            Action::MoveUp => vec![Key(KeyCode::W), Key(KeyCode::Up), Pad(Button::Up)],
+            Action::MoveDown => vec![Key(KeyCode::S), Key(KeyCode::Down), Pad(Button::Down)],
+            Action::MoveLeft => vec![Key(KeyCode::A), Key(KeyCode::Left), Pad(Button::Left)],
+            Action::MoveRight => vec![Key(KeyCode::D), Key(KeyCode::Right), Pad(Button::Right)],
+            Action::Sprint => vec![Key(KeyCode::LeftShift), Pad(Button::LB)],
+            Action::Fire => vec![Mouse(MouseButton::Left), Key(KeyCode::Space), Pad(Button::RT)],
+            Action::Pause => vec![Key(KeyCode::Escape), Key(KeyCode::P), Pad(Button::Start)],
+            Action::Restart => vec![Key(KeyCode::R), Pad(Button::A)],
+            Action::RetryArena => vec![Key(KeyCode::T), Pad(Button::X)],
+        }).collect();
+        Bindings { map }
+    }
+}
+
+fn path() -> std::path::PathBuf { storage::data_dir().join("bindings.txt") }
+
+impl Bindings {
+    // defaults for anything the file doesn't mention (or gets wrong)
+    pub fn load() -> Bindings {
+        let mut b = Bindings::default();
+        let Ok(text) = std::fs::read_to_string(path()) else { return b; };
+        for (ln, raw) in text.lines().enumerate() {
+            let line = raw.split('#').next().unwrap_or("").trim();
+            let mut f = line.split_whitespace();
+            let Some(name) = f.next() else { continue; };
+            let Some(a) = ACTIONS.into_iter().find(|a| a.key() == name) else { eprintln!("bindings.txt line {}: unknown action `{}`", ln + 1, name); continue; };
+            let binds: Vec<Binding> = f.filter_map(|t| Binding::parse(t).or_else(|| { eprintln!("bindings.txt line {}: unknown binding `{}`", ln + 1, t); None })).take(MAX_BINDINGS).collect();
+            b.map[index(a)] = binds;
+        }
+        b
+    }
+
+    pub fn save(&self) {
+        let mut text = String::from("# zombie_shooter bindings: <action> <binding>... (key names, Mouse:Left/Right/Middle or Pad:A/B/X/Y/LB/RB/LT/RT/Back/Start/L3/R3/Up/Down/Left/Right)\n");
+        for (a, binds) in ACTIONS.iter().zip(&self.map) {
+            text += a.key();
+            for b in binds { text += " "; text += &b.file_name(); }
+            text += "\n";
+        }
+        if let Err(e) = storage::write_atomic(&path(), &text) { eprintln!("couldn't save bindings: {}", e); }
+    }
+
+    pub fn of(&self, a: Action) -> &[Binding] { &self.map[index(a)] }
+    fn down(&self, a: Action, src: Source) -> bool { self.of(a).iter().any(|b| b.down(src)) }
+    pub fn pressed(&self, a: Action) -> bool { self.of(a).iter().any(|b| b.pressed()) }
+
+    // first binding's name, for prompts like "R restart"
+    pub fn label(&self, a: Action) -> String { self.of(a).first().map_or_else(|| "(unbound)".to_owned(), |b| b.label()) }
+
+    pub fn player(&self, src: Source) -> PlayerInput {
+        let axis = |neg: Action, pos: Action| self.down(pos, src) as i32 as f32 - self.down(neg, src) as i32 as f32;
+        let d = vec2(axis(Action::MoveLeft, Action::MoveRight), axis(Action::MoveUp, Action::MoveDown)).normalize_or_zero();
+        // the most pushed stick among this player's pads
+        let stick = |f: fn(usize) -> Vec2| src.pads().map(f).max_by(|a, b| a.length().total_cmp(&b.length())).unwrap_or(Vec2::ZERO);
+        let move_dir = if d == Vec2::ZERO { stick(gamepad::left_stick_on) } else { d };
+        let aim = Some(stick(gamepad::right_stick_on)).filter(|v| *v != Vec2::ZERO).map(Vec2::normalize);
+        let pad_fire = self.of(Action::Fire).iter().any(|b| matches!(b, Binding::Pad(_)) && b.down(src));
+        PlayerInput { move_dir, sprint: self.down(Action::Sprint, src), fire: self.down(Action::Fire, src), aim, assist: pad_fire && aim.is_none() }
+    }
+
+    // another action live at the same time as `a` that already uses `b`
+    pub fn conflict(&self, a: Action, b: Binding) -> Option<Action> {
+        ACTIONS.into_iter().find(|o| *o != a && o.context() == a.context() && self.of(*o).contains(&b))
+    }
+
+    // `b` becomes a's first binding (taking it away from anything it conflicts with)
+    pub fn bind(&mut self, a: Action, b: Binding) {
+        while let Some(o) = self.conflict(a, b) { self.map[index(o)].retain(|x| *x != b); }
+        let list = &mut self.map[index(a)];
+        list.retain(|x| *x != b);
+        list.insert(0, b);
+        list.truncate(MAX_BINDINGS);
+    }
+
+    pub fn clear(&mut self, a: Action) { self.map[index(a)].clear(); }
+
+    // actions with bindings that collide with another action's (e.g. from a hand-edited file)
+    pub fn has_conflict(&self, a: Action) -> bool { self.of(a).iter().any(|b| self.conflict(a, *b).is_some()) }
+}
@@ -0,0 +1,495 @@
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+pub mod arena;
+pub mod bot;
+pub mod coop;
+pub mod daily;
+pub mod difficulty;
+pub mod director;
+pub mod env;
+pub mod events;
+pub mod gamepad;
+pub mod input;
+pub mod map;
+pub mod mission;
+pub mod mode;
+pub mod net;
+pub mod scores;
+pub mod stats;
+pub mod storage;
+use arena::{Arena, Cover, push_out_of_box};
+use coop::Coop;
+use daily::{Daily, Modifier};
+use difficulty::{Difficulty, Preset, scaled};
+use director::Director;
+use events::{Cause, Event};
+use input::PlayerInput;
+use mission::Objective;
+use mode::{Mode, Outcome};
+use stats::Stats;
+
+// ---- The simulation: a run's state and the rules that advance it ----
+// Everything here works without a window, so the game (main.rs), the dedicated
+// server (bin/zombie_shooter_server.rs) and the balance simulator
+// (bin/zombie_shooter_balance.rs) share it: Game holds one run, step()
+// advances it a frame given each player's input, intermission() counts down
+// between waves; env.rs wraps the same loop for bots, and bot.rs is one. Drawing,
+// menus and everything saved per player stay in the game.
+// Only the gamepad and key-binding modules (behind PlayerInput) need macroquad's
+// window, and the simulation never calls them.
+// -----------------------------------------------
+
+const PLAYER_BASE_SPEED: f32 = 6.0;
+const PLAYER_SPRINT_SPEED: f32 = 9.5;
+pub const PLAYER_EYE: f32 = 0.8; // for drawing gun offset
+pub const PLAYER_MAX_HP: i32 = 6;
+
+const BULLET_SPEED: f32 = 28.0;
+pub const BULLET_RADIUS: f32 = 0.18;
+const BULLET_COOLDOWN_BASE: f32 = 0.20;
+const AIM_ASSIST_RANGE: f32 = 14.0;
+
+const ZOMBIE_RADIUS: f32 = 0.55;
+const FAST_ZOMBIE_RADIUS: f32 = 0.45;
+const TANK_ZOMBIE_RADIUS: f32 = 0.7;
+
+const SPAWN_SAFE_RADIUS: f32 = 8.0; // never spawn closer to the player than this
+const SPAWN_BEHIND_SAFE: f32 = 13.0; // ... or this, behind the player (off-camera side)
+pub const SPAWN_TELEGRAPH: f32 = 0.9; // seconds a zombie spends rising before it's active
+
+pub const BOMB_RADIUS: f32 = 4.2;
+const BOMB_COVER_DAMAGE: f32 = 7.0;
+
+const PICKUP_INTERVAL: f32 = 20.0; // seconds between powerups at map pickup points
+
+#[derive(Clone, Copy)]
+pub struct Player {
+    pub pos: Vec3, pub hp: i32, pub cd: f32, pub rapid_left: f32, pub slow_left: f32,
+    pub downed: bool, pub revive: f32, // co-op: out of the fight until a teammate has stood by for REVIVE_TIME
+    pub score: u32, // own kills, for separate co-op scores; Game::score is the total
+}
+
+#[derive(Clone, Copy)]
+pub struct Bullet { pub pos: Vec3, pub vel: Vec3, pub ttl: f32, pub owner: usize }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ZKind { Normal, Fast, Tank }
+
+#[derive(Clone, Copy)]
+pub struct Zombie { pub id: u32, pub pos: Vec3, pub speed: f32, pub kind: ZKind, pub hp: i32, pub rise: f32 } // id: unique within a run
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Drop { Heal, Bomb, Rapid, Slow }
+
+#[derive(Clone, Copy)]
+pub struct Powerup { pub pos: Vec3, pub kind: Drop, pub ttl: f32 }
+
+// simulation randomness, split into streams so e.g. a rejected spawn spot doesn't
+// shift the drop sequence; seeded per run, which is what makes daily runs repeatable
+pub struct SimRng { spawn: RandGenerator, spot: RandGenerator, drop: RandGenerator }
+
+impl SimRng {
+    fn new(seed: u64) -> SimRng {
+        let stream = |salt: u64| { let r = RandGenerator::new(); r.srand(seed ^ salt); r };
+        SimRng { spawn: stream(0x5EED_0001), spot: stream(0x5EED_0002), drop: stream(0x5EED_0003) }
+    }
+}
+
+// everything that belongs to a single run; rebuilt from the arena template on restart
+pub struct Game {
+    pub mode: Mode, pub diff: Difficulty, pub coop: Coop, pub max_hp: i32,
+    pub elapsed: f32, pub spawn_timer: f32, pub outcome: Option<Outcome>,
+    pub objective: Option<Objective>, // missions only
+    pub players: Vec<Player>, pub bullets: Vec<Bullet>, pub zombies: Vec<Zombie>, pub drops: Vec<Powerup>, pub arena: Arena,
+    pub score: u32, pub combo: f32, pub combo_timer: f32,
+    pub chain: u32, // kills in the current combo
+    pub wave: u32, pub inter_timer: f32, pub spawn_budget_left: usize, pub pickup_timer: f32,
+    pub director: Director,
+    pub seed: u64, pub rng: SimRng, pub next_id: u32,
+    pub stats: Stats,
+    pub events: Vec<Event>, // this frame's, handed to the listeners by whoever runs the game
+    pub daily: Option<Daily>, // today's challenge: fixed seed + modifiers
+    pub recorded: bool, // result saved to the boards
+}
+
+impl Game {
+    pub fn new(arena: Arena, mode: Mode, diff: Difficulty, coop: Coop, seed: u64) -> Game {
+        let max_hp = mode.max_hp(scaled(PLAYER_MAX_HP, diff.player_hp).max(1));
+        let rng = SimRng::new(seed);
+        // player 2 starts beside player 1 when there's room
+        let spawn = arena.spawn_pos();
+        let players = (0..coop.players()).map(|i| {
+            let side = spawn + vec3(1.5 * i as f32, 0.0, 0.0);
+            let pos = if arena.is_open(side, 0.5) { side } else { spawn };
+            Player { pos, hp: max_hp, cd: 0.0, rapid_left: 0.0, slow_left: 0.0, downed: false, revive: 0.0, score: 0 }
+        }).collect();
+        Game {
+            mode, diff, coop, max_hp,
+            elapsed: 0.0, spawn_timer: 0.0, outcome: None,
+            objective: Objective::new(mode, &arena, &rng.spot),
+            players,
+            bullets: vec![], zombies: vec![], drops: vec![], arena,
+            score: 0, combo: 1.0, combo_timer: 0.0, chain: 0,
+            wave: 1, inter_timer: 0.0, spawn_budget_left: wave_spawn_budget(1, &diff), pickup_timer: PICKUP_INTERVAL,
+            director: Director::new(),
+            seed, rng, next_id: 0, stats: Stats::default(), daily: None, recorded: false,
+            events: if mode.has_waves() { vec![Event::WaveStarted { wave: 1, at: 0.0 }] } else { vec![] },
+        }
+    }
+
+    // the daily challenge is always solo Waves on Normal; the director sits out so every run sees the same pacing
+    pub fn daily(d: Daily) -> Game {
+        let mut g = Game::new(Arena::generate(d.seed), Mode::Waves, Difficulty::preset(Preset::Normal), Coop::Solo, d.seed);
+        g.director.adaptive = false;
+        g.daily = Some(d);
+        g
+    }
+
+    pub fn has(&self, m: Modifier) -> bool { self.daily.as_ref().is_some_and(|d| d.has(m)) }
+    fn heal_drops(&self) -> bool { self.mode.heals() && !self.has(Modifier::NoHeals) }
+}
+
+// what a frame of a live run led to
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Step { Playing, WaveCleared, Over }
+
+// one frame of a live run: spawning, movement, combat, pickups and the objective.
+// inputs[i] drives players[i]; everything that happened goes onto g.events
+pub fn step(g: &mut Game, dt: f32, inputs: &[PlayerInput]) -> Step {
+    tick_timers(g, dt);
+    g.elapsed += dt;
+
+    // spawn logic, paced by the director
+    let team_hp = g.players.iter().filter(|p| !p.downed).map(|p| p.hp).sum();
+    g.director.update(dt, team_hp, g.max_hp * g.players.len() as i32);
+    if g.mode.has_waves() {
+        let max_alive = ((10 + g.wave as usize * 2) as f32 * g.director.spawn_scale()) as usize;
+        if g.zombies.len() < max_alive && g.spawn_budget_left > 0 {
+            let batch = (1 + (g.wave as usize / 2)).min(g.spawn_budget_left).min(max_alive - g.zombies.len());
+            // no valid spot this frame (player camping a zone) just defers the spawn
+            for _ in 0..batch { if let Some(z) = spawn_zombie(g) { add_zombie(g, z); g.spawn_budget_left -= 1; } }
+        }
+    } else {
+        // one continuous horde ramping with time; `wave` just tracks the ramp
+        g.wave = mode::ramp_wave(g.elapsed);
+        g.spawn_timer -= dt;
+        let scale = g.director.spawn_scale();
+        let max_alive = ((10 + g.wave as usize * 2) as f32 * scale) as usize;
+        if g.spawn_timer <= 0.0 && g.zombies.len() < max_alive {
+            if let Some(z) = spawn_zombie(g) { add_zombie(g, z); }
+            g.spawn_timer = mode::ramp_interval(g.elapsed) / scale.max(0.1);
+        }
+    }
+
+    // map pickup points periodically offer a random powerup
+    g.pickup_timer -= dt;
+    if g.pickup_timer <= 0.0 && !g.arena.pickups.is_empty() {
+        g.pickup_timer = PICKUP_INTERVAL;
+        let p = g.arena.pickups[g.rng.drop.gen_range(0, g.arena.pickups.len())];
+        let pos = vec3(p.x, 0.5, p.y);
+        if !g.drops.iter().any(|d| (d.pos - pos).length() < 1.0) {
+            let kinds: &[Drop] = if g.heal_drops() { &[Drop::Heal, Drop::Bomb, Drop::Rapid, Drop::Slow] } else { &[Drop::Bomb, Drop::Rapid, Drop::Slow] };
+            let kind = kinds[g.rng.drop.gen_range(0, kinds.len())];
+            g.drops.push(Powerup { pos, kind, ttl: 20.0 });
+        }
+    }
+
+    for (i, pin) in inputs.iter().enumerate().take(g.players.len()) {
+        if g.players[i].downed { continue; }
+        g.stats.distance += move_player(&mut g.players[i], &g.arena, pin, dt);
+
+        // shooting: forward along -Z, or wherever the right stick / aim assist points
+        let p = &g.players[i];
+        let cd = if p.rapid_left > 0.0 { BULLET_COOLDOWN_BASE * 0.45 } else { BULLET_COOLDOWN_BASE };
+        let cd = if g.has(Modifier::TriggerHappy) { cd * 0.6 } else { cd };
+        if pin.fire && g.players[i].cd <= 0.0 {
+            g.players[i].cd = cd;
+            let pos = g.players[i].pos;
+            let aim = pin.aim.or_else(|| if pin.assist { assist_aim(g, pos) } else { None }).unwrap_or(vec2(0.0, -1.0));
+            let dirz = vec3(aim.x, 0.0, aim.y);
+            g.bullets.push(Bullet { pos: pos + vec3(0.0, PLAYER_EYE, 0.0) + dirz * 0.8, vel: dirz * BULLET_SPEED, ttl: 1.8, owner: i });
+            g.events.push(Event::ShotFired { pos });
+        }
+    }
+
+    // bullets advance; walls stop them
+    for b in &mut g.bullets { b.pos += b.vel * dt; b.ttl -= dt; }
+    let (half, walls) = (g.arena.half, &g.arena.walls);
+    g.bullets.retain(|b| b.ttl > 0.0 && in_bounds(b.pos, half) && !walls.iter().any(|w| (b.pos - w.pos).abs().cmple(w.size * 0.5).all()));
+
+    // zombies seek the nearest player still standing (or the mission objective), slowed if slow_power active
+    let standing: Vec<Vec3> = g.players.iter().filter(|p| !p.downed).map(|p| p.pos).collect();
+    let slow_factor = if g.players.iter().any(|p| p.slow_left > 0.0) { 0.55 } else { 1.0 };
+    for z in &mut g.zombies {
+        // still climbing out of the ground
+        if z.rise > 0.0 { z.rise = (z.rise - dt).max(0.0); continue; }
+        let Some(near) = nearest(&standing, z.pos) else { continue; };
+        let target = g.objective.as_ref().map_or(near, |o| o.zombie_target(z.pos, near));
+        let to_p = (target - z.pos).with_y(0.0);
+        if to_p.length_squared() > 0.0004 { z.pos += to_p.normalize() * z.speed * slow_factor * dt; }
+        // simple arena clamp
+        z.pos.x = z.pos.x.clamp(-g.arena.half, g.arena.half); z.pos.z = z.pos.z.clamp(-g.arena.half, g.arena.half);
+        for w in &g.arena.walls { push_out_of_box(&mut z.pos, zombie_radius(z.kind), w.pos, w.size); }
+        // blocked by cover; zombies pressing against it chew through (tanks much faster)
+        for c in &mut g.arena.covers { if push_out_of_box(&mut z.pos, zombie_radius(z.kind), c.pos, c.size) { c.hp -= cover_smash_rate(z.kind) * dt; } }
+    }
+    g.arena.covers.retain(|c| c.hp > 0.0);
+
+    // bullet ↔ zombie
+    let mut zi = 0usize;
+    while zi < g.zombies.len() {
+        if g.zombies[zi].rise > 0.0 { zi += 1; continue; }
+        let mut killer = None;
+        let mut bj = 0usize;
+        while bj < g.bullets.len() {
+            let rad = zombie_radius(g.zombies[zi].kind);
+            // on the ground plane: bullets fly at gun height, above a zombie's centre
+            if (g.zombies[zi].pos - g.bullets[bj].pos).with_y(0.0).length() <= rad + BULLET_RADIUS {
+                let b = g.bullets.swap_remove(bj);
+                g.zombies[zi].hp -= 1;
+                g.events.push(Event::ZombieHit { kind: g.zombies[zi].kind, pos: g.zombies[zi].pos });
+                if g.zombies[zi].hp <= 0 { killer = Some(b.owner); }
+                break;
+            } else { bj += 1; }
+        }
+        if let Some(owner) = killer {
+            // score + combo, and chance to drop
+            let points = (10.0 * g.combo).round() as u32;
+            g.score += points; g.players[owner].score += points;
+            g.combo = if g.has(Modifier::DoubleCombo) { (g.combo + 0.5).min(8.0) } else { (g.combo + 0.25).min(4.0) }; g.combo_timer = 2.0;
+            g.chain += 1;
+            let (scale, heals) = (g.director.drop_scale() * g.diff.drop_chance, g.heal_drops());
+            maybe_drop(&mut g.drops, g.zombies[zi].pos, scale, heals, &g.rng.drop);
+            let z = g.zombies.swap_remove(zi);
+            g.events.push(Event::ZombieKilled { id: z.id, kind: z.kind, pos: z.pos, cause: Cause::Bullet, combo: g.combo, chain: g.chain });
+        } else { zi += 1; }
+    }
+
+    // zombie ↔ player; at 0 HP a player is downed
+    let mut k = 0usize;
+    while k < g.zombies.len() {
+        let z = g.zombies[k];
+        let rad = zombie_radius(z.kind);
+        let hit = if z.rise > 0.0 { None } else { g.players.iter().position(|p| !p.downed && (z.pos - p.pos).length() <= rad + 0.5) };
+        if let Some(i) = hit {
+            g.zombies.swap_remove(k);
+            let p = &mut g.players[i];
+            p.hp -= 1;
+            g.events.push(Event::PlayerDamaged { zombie: z.id, source: z.kind, amount: 1, hp: p.hp });
+            if p.hp <= 0 { p.downed = true; p.revive = 0.0; g.events.push(Event::PlayerDowned { player: i, pos: p.pos }); }
+        } else { k += 1; }
+    }
+
+    // a teammate standing next to a downed player picks them back up
+    for i in 0..g.players.len() {
+        if !g.players[i].downed { continue; }
+        let pos = g.players[i].pos;
+        let helped = g.players.iter().any(|o| !o.downed && (o.pos - pos).length() <= coop::REVIVE_RADIUS);
+        let p = &mut g.players[i];
+        p.revive = if helped { p.revive + dt } else { (p.revive - dt).max(0.0) };
+        if p.revive >= coop::REVIVE_TIME {
+            p.downed = false; p.revive = 0.0; p.hp = (g.max_hp / 2).max(1);
+            g.events.push(Event::PlayerRevived { player: i, pos });
+        }
+    }
+
+    // powerup pickups
+    let mut di = 0usize;
+    while di < g.drops.len() {
+        g.drops[di].ttl -= dt; if g.drops[di].ttl <= 0.0 { g.drops.swap_remove(di); continue; }
+        let at = g.drops[di].pos;
+        if let Some(i) = g.players.iter().position(|p| !p.downed && (at - p.pos).length() < 1.0) {
+            let kind = g.drops[di].kind;
+            g.events.push(Event::PowerupPicked { kind, pos: g.players[i].pos });
+            let killed = apply_powerup(&mut g.players[i], g.max_hp, kind, &mut g.zombies, &mut g.arena.covers);
+            let points = killed.len() as u32 * 15;
+            g.score += points; g.players[i].score += points;
+            for z in killed {
+                g.events.push(Event::ZombieKilled { id: z.id, kind: z.kind, pos: z.pos, cause: Cause::Bomb, combo: g.combo, chain: g.chain });
+            }
+            g.drops.swap_remove(di);
+        } else { di += 1; }
+    }
+
+    // mission objective
+    let standing: Vec<Vec3> = g.players.iter().filter(|p| !p.downed).map(|p| p.pos).collect();
+    let mission = g.objective.as_mut().and_then(|o| o.update(dt, &standing, &mut g.zombies, &mut g.events));
+
+    // run over (everyone down / out of time / mission decided)? wave cleared?
+    let hp = g.players.iter().filter(|p| !p.downed).map(|p| p.hp).max().unwrap_or(0);
+    g.outcome = g.mode.outcome(hp, g.elapsed).or(mission);
+    if let Some(outcome) = g.outcome {
+        g.events.push(Event::GameOver { outcome, score: g.score, wave: g.wave, secs: g.elapsed });
+        Step::Over
+    } else if g.mode.has_waves() && g.zombies.is_empty() && g.spawn_budget_left == 0 {
+        g.inter_timer = 2.0;
+        g.events.push(Event::WaveCleared { wave: g.wave, at: g.elapsed });
+        Step::WaveCleared
+    } else { Step::Playing }
+}
+
+// input movement, kept inside the arena and out of walls and cover; returns the distance
+// covered. Network clients run this too, to predict their own player (net.rs)
+pub fn move_player(p: &mut Player, arena: &Arena, pin: &PlayerInput, dt: f32) -> f32 {
+    let dir = vec3(pin.move_dir.x, 0.0, pin.move_dir.y);
+    let speed = if pin.sprint { PLAYER_SPRINT_SPEED } else { PLAYER_BASE_SPEED }; // dir is shorter for a half-pushed stick
+    let before = p.pos;
+    p.pos += dir * speed * dt;
+    p.pos.x = p.pos.x.clamp(-arena.half + 1.0, arena.half - 1.0);
+    p.pos.z = p.pos.z.clamp(-arena.half + 1.0, arena.half - 1.0);
+    for w in &arena.walls { push_out_of_box(&mut p.pos, 0.5, w.pos, w.size); }
+    for c in &arena.covers { push_out_of_box(&mut p.pos, 0.5, c.pos, c.size); }
+    (p.pos - before).length()
+}
+
+pub fn nearest(points: &[Vec3], from: Vec3) -> Option<Vec3> {
+    points.iter().copied().min_by(|a, b| (*a - from).length_squared().total_cmp(&(*b - from).length_squared()))
+}
+
+// fresh run on the same setup; generated arenas roll a new seed unless `same_arena`
+pub fn restart(g: &Game, template: &mut Arena, same_arena: bool) -> Game {
+    if let Some(d) = &g.daily { return Game::daily(d.clone()); }
+    if let (Some(_), false) = (template.seed, same_arena) { *template = Arena::generate(arena::random_seed()); }
+    Game::new(template.clone(), g.mode, g.diff, g.coop, arena::random_seed())
+}
+
+// per-frame countdowns; only while the run is live, so pausing freezes them
+pub fn tick_timers(g: &mut Game, dt: f32) {
+    for p in &mut g.players { p.cd -= dt; p.rapid_left = (p.rapid_left - dt).max(0.0); p.slow_left = (p.slow_left - dt).max(0.0); }
+    g.combo_timer = (g.combo_timer - dt).max(0.0); if g.combo_timer == 0.0 { g.combo = 1.0; g.chain = 0; }
+}
+
+// a frame of the break after Step::WaveCleared; true once the next wave has started
+pub fn intermission(g: &mut Game, dt: f32) -> bool {
+    tick_timers(g, dt);
+    g.inter_timer -= dt;
+    if g.inter_timer > 0.0 { return false; }
+    g.wave += 1; g.spawn_budget_left = wave_spawn_budget(g.wave, &g.diff);
+    g.events.push(Event::WaveStarted { wave: g.wave, at: g.elapsed });
+    // small heal each wave; anyone still down gets back up
+    for (i, p) in g.players.iter_mut().enumerate() {
+        if p.downed { p.downed = false; p.revive = 0.0; p.hp = 1; g.events.push(Event::PlayerRevived { player: i, pos: p.pos }); }
+        else if g.mode.heals() { p.hp = (p.hp + scaled(1, g.diff.wave_heal)).min(g.max_hp); }
+    }
+    true
+}
+
+pub fn run_record(g: &Game, name: String) -> scores::Record {
+    scores::Record {
+        name, score: g.score, wave: g.wave, mode: g.mode.key().to_owned(), diff: g.diff.key(),
+        secs: g.elapsed as u32, date: storage::civil_date(storage::days_today()),
+    }
+}
+
+pub fn clock(secs: f32) -> String { let s = secs.max(0.0) as u32; format!("{}:{:02}", s / 60, s % 60) }
+
+// pad players without a right stick push aim at the nearest risen zombie in range
+pub fn assist_aim(g: &Game, from: Vec3) -> Option<Vec2> {
+    let p = vec2(from.x, from.z);
+    g.zombies.iter().filter(|z| z.rise <= 0.0).map(|z| vec2(z.pos.x, z.pos.z) - p)
+        .filter(|d| d.length() < AIM_ASSIST_RANGE).min_by(|a, b| a.length().total_cmp(&b.length())).map(Vec2::normalize_or_zero)
+}
+
+// the director's toughness > 0 favours tanks and fast zombies, < 0 normals
+fn spawn_zombie(g: &Game) -> Option<Zombie> {
+    let (wave, toughness, diff) = (g.wave, g.director.toughness(), &g.diff);
+    // spot first, with room for a tank (the biggest kind): a failed try then costs no
+    // spawn roll, so the kind sequence stays the same however crowded the map is
+    let players: Vec<Vec3> = g.players.iter().map(|p| p.pos).collect();
+    let pos = find_spawn_spot(&players, &g.arena, TANK_ZOMBIE_RADIUS, &g.rng.spot)?;
+    // choose type weighted by wave
+    let roll = g.rng.spawn.gen_range(0.0, 1.0);
+    let mut tank_chance = (0.15 + wave as f32 * 0.01).min(0.35) * (1.0 + toughness);
+    if g.has(Modifier::TankRush) { tank_chance *= 2.0; }
+    if g.has(Modifier::OnlyFast) { tank_chance = -1.0; }
+    let (kind, speed, hp) = if roll < tank_chance { // tank chance grows
+        (ZKind::Tank, 1.6, 3)
+    } else if roll < 0.55 + 0.15 * toughness || g.has(Modifier::OnlyFast) { // fast
+        (ZKind::Fast, 3.6 + wave as f32 * 0.05, 1)
+    } else { // normal
+        (ZKind::Normal, 2.4 + wave as f32 * 0.03, 2)
+    };
+    Some(Zombie { id: g.next_id, pos, speed: speed * diff.zombie_speed, kind, hp: scaled(hp, diff.zombie_hp).max(1), rise: SPAWN_TELEGRAPH })
+}
+
+fn add_zombie(g: &mut Game, z: Zombie) {
+    g.events.push(Event::ZombieSpawned { id: z.id, kind: z.kind, pos: z.pos });
+    g.zombies.push(z); g.next_id += 1;
+}
+
+// random spot in a map zone (or on a ring around player 1 when the map has none)
+// that is open floor and far enough from every player; None if nothing fits this try
+fn find_spawn_spot(players: &[Vec3], arena: &Arena, radius: f32, rng: &RandGenerator) -> Option<Vec3> {
+    let player = players[0];
+    for _ in 0..12 {
+        let angle = rng.gen_range(0.0, 360.0f32).to_radians();
+        let pos = if arena.zones.is_empty() {
+            let r = rng.gen_range(14.0f32.min(arena.half - 3.0), arena.half - 1.5);
+            vec3(player.x + angle.sin()*r, 0.5, player.z + angle.cos()*r)
+        } else {
+            let zone = arena.zones[rng.gen_range(0, arena.zones.len())];
+            let r = zone.radius * rng.gen_range(0.0f32, 1.0).sqrt();
+            vec3(zone.pos.x + angle.sin()*r, 0.5, zone.pos.y + angle.cos()*r)
+        };
+        let clear = players.iter().all(|p| {
+            let to = (pos - *p).with_y(0.0);
+            to.length() >= if to.z > 0.0 { SPAWN_BEHIND_SAFE } else { SPAWN_SAFE_RADIUS }
+        });
+        if clear && arena.is_open(pos, radius) { return Some(pos); }
+    }
+    None
+}
+
+// `scale` multiplies the overall drop chance, keeping the mix between kinds
+fn maybe_drop(out: &mut Vec<Powerup>, pos: Vec3, scale: f32, heals: bool, rng: &RandGenerator) {
+    let p = rng.gen_range(0.0, 1.0) / scale;
+    if p < 0.22 {
+        let kind = if p < 0.07 { Drop::Heal } else if p < 0.12 { Drop::Bomb } else if p < 0.18 { Drop::Rapid } else { Drop::Slow };
+        if !heals && matches!(kind, Drop::Heal) { return; }
+        out.push(Powerup { pos, kind, ttl: 12.0 });
+    }
+}
+
+// returns any zombies it killed
+fn apply_powerup(player: &mut Player, max_hp: i32, kind: Drop, zombies: &mut Vec<Zombie>, covers: &mut Vec<Cover>) -> Vec<Zombie> {
+    let mut killed = vec![];
+    match kind {
+        Drop::Heal => { player.hp = (player.hp + 2).min(max_hp); }
+        Drop::Bomb => {
+            let radius = BOMB_RADIUS;
+            let mut i=0; while i < zombies.len() { if (zombies[i].pos - player.pos).length() <= radius { killed.push(zombies.swap_remove(i)); } else { i+=1; } }
+            for c in covers.iter_mut() { if (c.pos - player.pos).with_y(0.0).length() <= radius + c.size.x * 0.5 { c.hp -= BOMB_COVER_DAMAGE; } }
+            covers.retain(|c| c.hp > 0.0);
+        }
+        Drop::Rapid => { player.rapid_left = 6.0; }
+        Drop::Slow => { player.slow_left = 6.0; }
+    }
+    killed
+}
+
+pub fn zombie_radius(kind: ZKind) -> f32 { match kind { ZKind::Tank => TANK_ZOMBIE_RADIUS, ZKind::Fast => FAST_ZOMBIE_RADIUS, ZKind::Normal => ZOMBIE_RADIUS } }
+
+// cover hp lost per second of contact
+fn cover_smash_rate(kind: ZKind) -> f32 { match kind { ZKind::Tank => 6.0, ZKind::Normal => 1.0, ZKind::Fast => 0.5 } }
+
+fn in_bounds(p: Vec3, half: f32) -> bool { p.x.abs() <= half+2.0 && p.z.abs() <= half+2.0 }
+
+fn wave_spawn_budget(wave: u32, diff: &Difficulty) -> usize { (((8 + (wave as usize)*5).min(120) as f32 * diff.spawn_budget).round() as usize).max(1) }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bullet_hits_a_normal_zombie() {
+        let mut g = Game::new(Arena::empty("test", 20.0), Mode::Waves, Difficulty::preset(Preset::Normal), Coop::Solo, 1);
+        g.spawn_budget_left = 0; // only ours
+        let p = g.players[0].pos;
+        let at = vec3(p.x, 0.5, p.z - 6.0); // standing on the ground, where spawns put them
+        g.zombies.push(Zombie { id: 99, pos: at, speed: 0.0, kind: ZKind::Normal, hp: 2, rise: 0.0 });
+        let fire = PlayerInput { fire: true, aim: Some(vec2(0.0, -1.0)), ..Default::default() };
+        for _ in 0..30 { step(&mut g, 1.0 / 60.0, std::slice::from_ref(&fire)); }
+        assert!(g.zombies.iter().find(|z| z.id == 99).is_none_or(|z| z.hp < 2), "the bullet went straight through");
+    }
+}
@@ -1,280 +1,833 @@
 use macroquad::prelude::*;
 
+use zombie_shooter::{arena, bot, coop, daily, difficulty, events, gamepad, input, map, mode, net, scores, stats, storage};
+use zombie_shooter::{clock, intermission, restart, run_record, step, zombie_radius, Drop, Game, Step, ZKind, BOMB_RADIUS, BULLET_RADIUS, SPAWN_TELEGRAPH};
+mod achievements;
+mod editor;
+mod fx;
+mod settings;
+mod spectator;
+mod ui;
+use achievements::Achievements;
+use arena::Arena;
+use bot::Bot;
+use coop::{Coop, View};
+use daily::Daily;
+use difficulty::{Difficulty, Preset, PRESETS};
+use editor::{Editor, EditorAction};
+use fx::Fx;
+use input::{Action, Binding, Bindings, PlayerInput, Source, ACTIONS};
+use mode::{Mode, MODES, Outcome};
+use scores::Scores;
+use settings::Settings;
+use spectator::Spectator;
+use ui::Row;
+
 // ---- Zombie Shooter 3D (Waves + Powerups) ----
-// Controls: WASD move, Shift sprint, LMB shoot, R restart
+// Starts on a title menu (play, mode select, settings, high scores, achievements, quit);
+// video/camera/HUD options persist in settings.txt in the data dir (settings.rs).
+// menus work with W/S/Enter or the mouse (ui.rs). Esc pauses a run.
+// Controls (defaults; rebind under Settings -> Controls, see input.rs): WASD move,
+// Shift sprint, LMB shoot, R restart, T retry same arena; M map select.
+// Pads (gamepad.rs; Linux only, Xbox layout): left stick move, right stick aim
+// (else aim assist), RT fire. Elsewhere local co-op needs player 2 to join online.
+// E on the map select screen opens the map editor (controls in editor.rs).
+// Difficulty is picked there too: ←/→ preset; for Custom, Tab picks a multiplier and +/- tunes it.
+// Q cycles the game mode (Waves, Endless, Time Attack, Hardcore and the missions; see mode.rs).
+// Maps are loaded from maps/*.map (format in map.rs), or generated from a seed shown
+// on the HUD; replay a generated one with `--seed <n>`. The daily challenge (daily.rs)
+// derives arena, spawn/drop sequence and rule modifiers from today's date.
 // Camera: simple chase cam. Zombies have types & waves scale difficulty.
 // Powerups: Heal, Bomb, RapidFire, Slow.
+// Cover blocks movement and crumbles under zombie contact (tanks fastest) and bombs.
+// Zombies spawn in the map's zones, never near the player or inside geometry, and
+// rise out of a marked patch of ground before they start hunting.
+// An AI director (director.rs) paces spawns, zombie mix and drops within each wave.
+// High scores and run history persist in the user's data dir (scores.rs), with a
+// table per mode and difficulty; H on the map select screen shows them. Each run
+// keeps stats (stats.rs): Tab on the results screen flips to the stats page, X
+// exports them as JSON + a CSV row.
+// Achievements (achievements.rs, defined in achievements.txt) unlock with a toast;
+// C on the map select screen lists them. 2 on the map select screen switches to local
+// co-op (coop.rs): a second player on a controller, revives, shared or split camera.
+// The simulation reports what happens as typed events (events.rs) that stats,
+// achievements, the director and the effects in fx.rs listen to; `--log-events`
+// dumps them to stderr.
+// Online co-op (net.rs): `--host <port>` lets a second player join your co-op runs,
+// `--join <addr:port>` plays in theirs (or on a zombie_shooter_server, see
+// bin/zombie_shooter_server.rs); `--lag <ms>` / `--loss <%>` fake a bad line.
+// `--spectate <addr:port>` watches such a run with free, follow and top-down
+// cameras instead (spectator.rs).
+// Left alone on the title screen for ATTRACT_AFTER, the game plays a demo run with
+// the scripted bot (bot.rs) until any key; `--autoplay` has the bot play player 1
+// run after run (soak tests, difficulty baselines). Bot runs stay off the boards.
+// The simulation itself (Game, step()) lives in lib.rs; this file is the window,
+// the screens and the drawing.
 // -----------------------------------------------
 
-const PLAYER_BASE_SPEED: f32 = 6.0;
-const PLAYER_SPRINT_SPEED: f32 = 9.5;
-const PLAYER_EYE: f32 = 0.8; // for drawing gun offset
-const PLAYER_MAX_HP: i32 = 6;
-
-const BULLET_SPEED: f32 = 28.0;
-const BULLET_RADIUS: f32 = 0.18;
-const BULLET_COOLDOWN_BASE: f32 = 0.20;
-
-const ZOMBIE_RADIUS: f32 = 0.55;
-const FAST_ZOMBIE_RADIUS: f32 = 0.45;
-const TANK_ZOMBIE_RADIUS: f32 = 0.7;
-
-const ARENA_HALF: f32 = 24.0; // -24..+24 square
-
-#[derive(Clone, Copy)]
-struct Player { pos: Vec3, hp: i32, cd: f32, rapid_left: f32, slow_left: f32 }
-
-#[derive(Clone, Copy)]
-struct Bullet { pos: Vec3, vel: Vec3, ttl: f32 }
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Title, ModeSelect, Settings, Controls, MapSelect, HighScores, Achievements, Editor,
+    Running, InterWave, GameOver,
+    Remote, // someone else's run, joined over the network (net.rs)
+    Pause, ConfirmLeave, ConfirmDiscard, ConfirmExit, // overlays: drawn over the run / screen below them
+}
 
-#[derive(Clone, Copy)]
-enum ZKind { Normal, Fast, Tank }
+// screen stack: the top one runs; menus and dialogs push over what they cover and pop back to it
+struct Screens(Vec<State>);
 
-#[derive(Clone, Copy)]
-struct Zombie { pos: Vec3, speed: f32, kind: ZKind, hp: i32 }
+impl Screens {
+    fn top(&self) -> State { *self.0.last().unwrap_or(&State::Title) }
+    fn below(&self) -> State { self.0.iter().rev().nth(1).copied().unwrap_or(State::Title) }
+    fn push(&mut self, s: State) { self.0.push(s); }
+    fn pop(&mut self) { if self.0.len() > 1 { self.0.pop(); } }
+    fn set(&mut self, s: State) { self.0.pop(); self.0.push(s); } // replace the top
+    fn reset(&mut self) { self.0 = vec![State::Title]; }
+}
 
-#[derive(Clone, Copy)]
-enum Drop { Heal, Bomb, Rapid, Slow }
+const ATTRACT_AFTER: f32 = 30.0; // idle seconds on the title screen before the demo starts
+const DEMO_RESULTS: f32 = 6.0; // seconds a bot run's results stay up before the next run
 
-#[derive(Clone, Copy)]
-struct Powerup { pos: Vec3, kind: Drop, ttl: f32 }
+// the bot is playing: the title screen's attract demo (any input ends it) or `--autoplay`
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Demo { Attract, Soak }
 
-#[derive(PartialEq, Eq)]
-enum State { Running, InterWave, GameOver }
+// window options come from the settings file, so it's read once here and again in main
+fn window_conf() -> Conf { Settings::load().window_conf("Zombie Shooter 3D — Waves & Powerups") }
 
-#[macroquad::main("Zombie Shooter 3D — Waves & Powerups")]
+#[macroquad::main(window_conf)]
 async fn main() {
-    let mut player = Player { pos: vec3(0.0, 0.5, 0.0), hp: PLAYER_MAX_HP, cd: 0.0, rapid_left: 0.0, slow_left: 0.0 };
-    let mut bullets: Vec<Bullet> = vec![];
-    let mut zombies: Vec<Zombie> = vec![];
-    let mut drops: Vec<Powerup> = vec![];
-
-    let mut score: u32 = 0;
-    let mut combo: f32 = 1.0; let mut combo_timer: f32 = 0.0;
-    let mut wave: u32 = 1; let mut state = State::Running; let mut inter_timer = 0.0;
-    let mut spawn_budget_left = wave_spawn_budget(wave);
-
-    // camera shake
-    let mut shake: f32 = 0.0;
-
-    // place some cover boxes
-    let mut covers: Vec<Vec3> = vec![];
-    for i in -2..=2 { covers.push(vec3(i as f32 * 6.5, 0.6, 8.0)); }
-    for i in -1..=1 { covers.push(vec3(-12.0, 0.6, i as f32 * 6.0)); covers.push(vec3(12.0, 0.6, i as f32 * 6.0)); }
+    // hand-authored maps; entries 0 and 1 on the select screen are a freshly generated arena and the daily challenge
+    let mut maps = map::load_dir(map::MAP_DIR);
+    let mut selected = 0usize;
+    let mut mode = Mode::Waves;
+    let mut coop = Coop::Solo;
+    let mut diff = Difficulty::preset(Preset::Normal);
+    let mut custom = Difficulty::preset(Preset::Custom); // remembered while flipping through presets
+    let mut custom_field = 0usize;
+    let mut settings = Settings::load();
+    let boot = settings; // vsync / MSAA as the window was created
+    let mut bindings = Bindings::load();
+    let mut capture: Option<Action> = None; // controls screen: waiting for a key for this action
+    let mut clash: Option<(Action, Binding, Action)> = None; // ...which another action already uses
+    let mut fx = Fx::new();
+    fx.intensity = settings.shake;
+    // cursor per menu
+    let (mut title_sel, mut mode_sel, mut settings_sel, mut pause_sel, mut confirm_sel) = (0usize, 0usize, 0usize, 0usize, 1usize);
+    let mut controls_sel = 0usize;
+    let mut log = events::Logger { enabled: std::env::args().any(|a| a == "--log-events") };
+    let mut ach = Achievements::load();
+    for w in &ach.warnings { eprintln!("achievements: {}", w); }
+    let mut scores = Scores::load();
+    for w in &scores.warnings { eprintln!("scores: {}", w); }
+    let mut name_entry: Option<String> = None; // typing a name for a new high score
+    let mut new_rank: Option<usize> = None;
+    let (mut hs_mode, mut hs_diff) = (mode, diff.key()); // high-score screen: whose table is showing
+    let mut show_stats = false; // results screen: stats page instead of the summary
+    let mut export_msg: Option<String> = None;
+    let mut editor: Option<Editor> = None;
+    let mut playtest = false; // run was started from the editor; Esc / E go back to it
+    let mut daily_board: Vec<daily::Entry> = vec![];
+
+    // `--seed <n>` skips the select screen and replays that generated layout
+    let args: Vec<String> = std::env::args().collect();
+    let seed_arg: Option<u64> = args.iter().position(|a| a == "--seed").and_then(|i| args.get(i + 1)).and_then(|s| s.parse().ok());
+    let mut template = Arena::generate(seed_arg.unwrap_or_else(arena::random_seed));
+    let mut g = Game::new(template.clone(), mode, diff, coop, arena::random_seed());
+    let mut demo = args.iter().any(|a| a == "--autoplay").then_some(Demo::Soak);
+    let mut screens = Screens(if seed_arg.is_some() || demo.is_some() { vec![State::Title, State::Running] } else { vec![State::Title] });
+    let mut bot = Bot::new();
+    let (mut idle, mut demo_timer) = (0.0f32, 0.0f32);
+
+    // online co-op
+    let arg = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).cloned();
+    let (lag, loss) = (arg("--lag").and_then(|v| v.parse().ok()).unwrap_or(0.0), arg("--loss").and_then(|v| v.parse().ok()).unwrap_or(0.0));
+    let mut host: Option<net::Host> = arg("--host").and_then(|port| match net::Link::bind(&format!("0.0.0.0:{}", port), lag, loss) {
+        Ok(link) => { eprintln!("net: hosting on UDP port {}", port); Some(net::Host::new(link)) }
+        Err(e) => { eprintln!("net: can't host on port {}: {}", port, e); None }
+    });
+    if host.is_some() { coop = Coop::Shared; }
+    let join = arg("--join").map(|addr| (addr, false)).or_else(|| arg("--spectate").map(|addr| (addr, true)));
+    let mut client: Option<net::Client> = join.and_then(|(addr, watch)| {
+        let to = std::net::ToSocketAddrs::to_socket_addrs(&addr).ok().and_then(|mut a| a.next());
+        match (to, net::Link::bind("0.0.0.0:0", lag, loss)) {
+            (Some(to), Ok(link)) => Some(net::Client::new(link, to, watch)),
+            (None, _) => { eprintln!("net: can't resolve {}", addr); None }
+            (_, Err(e)) => { eprintln!("net: no socket: {}", e); None }
+        }
+    });
+    if client.is_some() { screens.push(State::Remote); }
+    let mut spectator = Spectator::new();
+    prevent_quit(); // closing the window asks first
 
     loop {
         let dt = get_frame_time();
         if dt <= 0.0 { next_frame().await; continue; }
+        gamepad::update();
+        if let Some(h) = host.as_mut() { h.frame(&mut g, net_phase(&screens)); }
 
-        // timers
-        player.cd -= dt; player.rapid_left = (player.rapid_left - dt).max(0.0); player.slow_left = (player.slow_left - dt).max(0.0);
-        shake = (shake - dt * 4.0).max(0.0);
-        combo_timer = (combo_timer - dt).max(0.0); if combo_timer == 0.0 { combo = 1.0; }
+        fx.update(dt);
+        if is_quit_requested() && screens.top() != State::ConfirmExit { confirm_sel = 1; screens.push(State::ConfirmExit); }
 
         clear_background(Color::from_rgba(12, 14, 20, 255));
 
-        match state {
-            State::Running => {
-                // spawn logic while running this wave
-                let max_alive = 10 + wave as usize * 2;
-                if zombies.len() < max_alive && spawn_budget_left > 0 {
-                    let batch = (1 + (wave as usize / 2)).min(spawn_budget_left);
-                    for _ in 0..batch { zombies.push(spawn_zombie(wave, player.pos)); }
-                    spawn_budget_left -= batch;
-                }
+        let in_run = matches!(screens.top(), State::Running | State::InterWave | State::GameOver);
+        if demo == Some(Demo::Attract) && in_run && any_input() {
+            screens.reset();
+            next_frame().await; continue; // that key shouldn't also pick something on the title menu
+        }
 
-                // input movement
-                let mut dir = Vec3::ZERO;
-                if is_key_down(KeyCode::W) { dir.z -= 1.0; }
-                if is_key_down(KeyCode::S) { dir.z += 1.0; }
-                if is_key_down(KeyCode::A) { dir.x -= 1.0; }
-                if is_key_down(KeyCode::D) { dir.x += 1.0; }
-                if dir.length_squared() > 0.0 { dir = dir.normalize(); }
-                let speed = if is_key_down(KeyCode::LeftShift) { PLAYER_SPRINT_SPEED } else { PLAYER_BASE_SPEED };
-                player.pos += dir * speed * dt;
-                // keep inside arena, avoid overlapping cover a bit
-                player.pos.x = player.pos.x.clamp(-ARENA_HALF + 1.0, ARENA_HALF - 1.0);
-                player.pos.z = player.pos.z.clamp(-ARENA_HALF + 1.0, ARENA_HALF - 1.0);
-
-                // shooting (forward along -Z for simplicity)
-                let cd = if player.rapid_left > 0.0 { BULLET_COOLDOWN_BASE * 0.45 } else { BULLET_COOLDOWN_BASE };
-                if is_mouse_button_down(MouseButton::Left) && player.cd <= 0.0 {
-                    player.cd = cd;
-                    let dirz = vec3(0.0, 0.0, -1.0); // simple forward shot
-                    bullets.push(Bullet { pos: player.pos + vec3(0.0, PLAYER_EYE, 0.0) + dirz * 0.8, vel: dirz * BULLET_SPEED, ttl: 1.8 });
-                    shake = (shake + 0.25).min(1.0);
+        match screens.top() {
+            State::Title => {
+                // back here, any demo is over; sit idle long enough and another starts
+                demo = None;
+                idle = if any_input() { 0.0 } else { idle + dt };
+                if idle >= ATTRACT_AFTER {
+                    idle = 0.0;
+                    template = Arena::generate(arena::random_seed());
+                    g = Game::new(template.clone(), mode, diff, Coop::Solo, arena::random_seed());
+                    bot = Bot::new(); demo = Some(Demo::Attract); playtest = false;
+                    screens.push(State::Running);
                 }
-
-                // bullets advance
-                for b in &mut bullets { b.pos += b.vel * dt; b.ttl -= dt; }
-                bullets.retain(|b| b.ttl > 0.0 && in_bounds(b.pos));
-
-                // zombies seek player, slowed if slow_power active
-                let slow_factor = if player.slow_left > 0.0 { 0.55 } else { 1.0 };
-                for z in &mut zombies {
-                    let to_p = (player.pos - z.pos).with_y(0.0);
-                    if to_p.length_squared() > 0.0004 { z.pos += to_p.normalize() * z.speed * slow_factor * dt; }
-                    // simple arena clamp
-                    z.pos.x = z.pos.x.clamp(-ARENA_HALF, ARENA_HALF); z.pos.z = z.pos.z.clamp(-ARENA_HALF, ARENA_HALF);
+                ui::title("ZOMBIE SHOOTER 3D", 110.0);
+                let sub = format!("{}  •  {}", mode.name(), diff.name());
+                let tw = measure_text(&sub, None, 24, 1.0);
+                draw_text(&sub, screen_width()*0.5 - tw.width*0.5, 146.0, 24.0, GRAY);
+                let rows = ["Play", "Mode select", "Settings", "High scores", "Achievements", "Quit"].map(Row::button);
+                match ui::menu(&rows, &mut title_sel, 190.0) {
+                    ui::Action::Activate(0) => screens.push(State::MapSelect),
+                    ui::Action::Activate(1) => { mode_sel = MODES.iter().position(|m| *m == mode).unwrap_or(0); screens.push(State::ModeSelect); }
+                    ui::Action::Activate(2) => screens.push(State::Settings),
+                    ui::Action::Activate(3) => { (hs_mode, hs_diff) = (mode, diff.key()); screens.push(State::HighScores); }
+                    ui::Action::Activate(4) => screens.push(State::Achievements),
+                    ui::Action::Activate(_) | ui::Action::Back => { confirm_sel = 1; screens.push(State::ConfirmExit); }
+                    _ => {}
                 }
-
-                // bullet ↔ zombie
-                let mut zi = 0usize;
-                while zi < zombies.len() {
-                    let mut dead = false;
-                    let mut bj = 0usize;
-                    while bj < bullets.len() {
-                        let rad = match zombies[zi].kind { ZKind::Tank => TANK_ZOMBIE_RADIUS, ZKind::Fast => FAST_ZOMBIE_RADIUS, _ => ZOMBIE_RADIUS };
-                        if (zombies[zi].pos - bullets[bj].pos).length() <= rad + BULLET_RADIUS {
-                            bullets.swap_remove(bj);
-                            zombies[zi].hp -= 1;
-                            if zombies[zi].hp <= 0 { dead = true; }
-                            break;
-                        } else { bj += 1; }
+                ui::hint("W/S or ↑/↓ choose • Enter select • or use the mouse");
+            }
+            State::ModeSelect => {
+                ui::title("MODE", 90.0);
+                let rows = MODES.map(|m| Row::button(m.name()));
+                let action = ui::menu(&rows, &mut mode_sel, 130.0);
+                let blurb = MODES[mode_sel].blurb();
+                let tw = measure_text(blurb, None, 24, 1.0);
+                draw_text(blurb, screen_width()*0.5 - tw.width*0.5, 130.0 + MODES.len() as f32 * 44.0 + 30.0, 24.0, LIGHTGRAY);
+                match action {
+                    ui::Action::Activate(i) => { mode = MODES[i]; screens.set(State::MapSelect); }
+                    ui::Action::Back => screens.pop(),
+                    _ => {}
+                }
+                ui::hint("Enter pick and choose a map • Esc back");
+            }
+            State::Settings => {
+                ui::title("SETTINGS", 70.0);
+                let mut rows: Vec<Row> = settings.rows().into_iter().map(|(label, v)| Row::value(label, v)).collect();
+                let controls_row = rows.len();
+                rows.push(Row::button("Controls"));
+                rows.push(Row::button("Back"));
+                match ui::menu(&rows, &mut settings_sel, 100.0) {
+                    ui::Action::Adjust(i, d) => { settings.adjust(i, d); fx.intensity = settings.shake; }
+                    ui::Action::Activate(i) if i == controls_row => { controls_sel = 0; screens.push(State::Controls); }
+                    ui::Action::Activate(_) | ui::Action::Back => { settings.save(); screens.pop(); }
+                    _ => {}
+                }
+                let restart_note = settings.vsync != boot.vsync || settings.msaa != boot.msaa;
+                ui::hint(if restart_note { "VSync / MSAA changes apply the next time the game starts • Esc back" } else { "A/D or ←/→ change • Esc back (saves)" });
+            }
+            State::Controls => {
+                ui::title("CONTROLS", 70.0);
+                let mut rows: Vec<Row> = ACTIONS.iter().map(|a| {
+                    let keys: Vec<String> = bindings.of(*a).iter().map(|b| b.label()).collect();
+                    let keys = if keys.is_empty() { "unbound".to_owned() } else { keys.join(" / ") };
+                    let label = if bindings.has_conflict(*a) { format!("{} (conflict)", a.name()) } else { a.name().to_owned() };
+                    Row::info(&label, keys)
+                }).collect();
+                rows.push(Row::button("Reset to defaults"));
+                rows.push(Row::button("Back"));
+                let pads = gamepad::connected();
+                let pad = if pads.is_empty() { "No controller found".to_owned() } else { format!("Controllers: {}", pads.join(", ")) };
+                let tw = measure_text(&pad, None, 20, 1.0);
+                draw_text(&pad, screen_width()*0.5 - tw.width*0.5, screen_height() - 46.0, 20.0, GRAY);
+                if let Some((a, b, other)) = clash {
+                    let mut sel = controls_sel;
+                    ui::menu(&rows, &mut sel, 100.0); // drawn, not interactive
+                    ui::notice(&[&format!("{} is already bound to {}", b.label(), other.name()), &format!("Enter: use it for {} instead  •  Esc: cancel", a.name())]);
+                    if is_key_pressed(KeyCode::Enter) { bindings.bind(a, b); bindings.save(); clash = None; }
+                    if is_key_pressed(KeyCode::Escape) { clash = None; }
+                } else if let Some(a) = capture {
+                    let mut sel = controls_sel;
+                    ui::menu(&rows, &mut sel, 100.0);
+                    ui::notice(&[&format!("Press a key or mouse button for {}", a.name()), "Esc: cancel"]);
+                    if is_key_pressed(KeyCode::Escape) { capture = None; }
+                    else if let Some(b) = Binding::captured() {
+                        capture = None;
+                        match bindings.conflict(a, b) {
+                            Some(other) => clash = Some((a, b, other)),
+                            None => { bindings.bind(a, b); bindings.save(); }
+                        }
+                    }
+                } else {
+                    match ui::menu(&rows, &mut controls_sel, 100.0) {
+                        ui::Action::Activate(i) if i < ACTIONS.len() => capture = Some(ACTIONS[i]),
+                        ui::Action::Activate(i) if i == ACTIONS.len() => { bindings = Bindings::default(); bindings.save(); }
+                        ui::Action::Activate(_) | ui::Action::Back => screens.pop(),
+                        _ => {}
+                    }
+                    if controls_sel < ACTIONS.len() && (is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace)) {
+                        bindings.clear(ACTIONS[controls_sel]); bindings.save();
                     }
-                    if dead {
-                        // score + combo, and chance to drop
-                        score += (10.0 * combo).round() as u32;
-                        combo = (combo + 0.25).min(4.0); combo_timer = 2.0;
-                        maybe_drop(&mut drops, zombies[zi].pos);
-                        zombies.swap_remove(zi);
-                    } else { zi += 1; }
                 }
-
-                // zombie ↔ player
-                let mut k = 0usize;
-                while k < zombies.len() {
-                    let rad = match zombies[k].kind { ZKind::Tank => TANK_ZOMBIE_RADIUS, ZKind::Fast => FAST_ZOMBIE_RADIUS, _ => ZOMBIE_RADIUS };
-                    if (zombies[k].pos - player.pos).length() <= rad + 0.5 {
-                        zombies.swap_remove(k);
-                        player.hp -= 1; shake = (shake + 0.6).min(1.4);
-                        if player.hp <= 0 { state = State::GameOver; }
-                    } else { k += 1; }
+                ui::hint("Enter rebind (replaces the first key, keeps one more) • Del clear • Esc back");
+            }
+            State::Pause => {
+                render_world(&g, &fx, &settings);
+                draw_hud(&g, screens.below() == State::InterWave, settings.hud_scale, &bindings);
+                ui::dim();
+                ui::title("PAUSED", screen_height()*0.5 - 140.0);
+                let rows = ["Resume", "Restart", "Settings", "Quit to title"].map(Row::button);
+                match ui::menu(&rows, &mut pause_sel, screen_height()*0.5 - 100.0) {
+                    ui::Action::Activate(0) | ui::Action::Back => screens.pop(),
+                    ui::Action::Activate(1) => { g = restart(&g, &mut template, true); screens.pop(); screens.set(State::Running); }
+                    ui::Action::Activate(2) => screens.push(State::Settings),
+                    ui::Action::Activate(_) => { confirm_sel = 1; screens.push(State::ConfirmLeave); }
+                    _ => {}
                 }
-
-                // powerup pickups
-                let mut di = 0usize;
-                while di < drops.len() {
-                    drops[di].ttl -= dt; if drops[di].ttl <= 0.0 { drops.swap_remove(di); continue; }
-                    if (drops[di].pos - player.pos).length() < 1.0 {
-                        apply_powerup(&mut player, drops[di].kind, &mut zombies, &mut score);
-                        drops.swap_remove(di);
-                    } else { di += 1; }
+            }
+            State::ConfirmLeave => {
+                render_world(&g, &fx, &settings);
+                draw_hud(&g, false, settings.hud_scale, &bindings);
+                match ui::confirm("Abandon this run?", &mut confirm_sel) {
+                    Some(true) => screens.reset(),
+                    Some(false) => screens.pop(),
+                    None => {}
+                }
+            }
+            State::ConfirmExit => {
+                if matches!(screens.below(), State::Running | State::InterWave | State::Pause) { render_world(&g, &fx, &settings); draw_hud(&g, false, settings.hud_scale, &bindings); }
+                else { ui::title("ZOMBIE SHOOTER 3D", 110.0); }
+                match ui::confirm("Quit the game?", &mut confirm_sel) {
+                    Some(true) => break,
+                    Some(false) => screens.pop(),
+                    None => {}
+                }
+            }
+            State::MapSelect => {
+                draw_map_select(&maps, selected, mode, &diff, custom_field, coop, scores.best(mode.key(), &diff.key()));
+                let count = maps.len() + 2;
+                if is_key_pressed(KeyCode::Q) { mode = MODES[(MODES.iter().position(|m| *m == mode).unwrap_or(0) + 1) % MODES.len()]; }
+                if is_key_pressed(KeyCode::Key2) { coop = coop.next(); }
+                let step = if is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::D) { 1 } else if is_key_pressed(KeyCode::Left) || is_key_pressed(KeyCode::A) { PRESETS.len() - 1 } else { 0 };
+                if step != 0 {
+                    let i = (PRESETS.iter().position(|p| *p == diff.preset).unwrap_or(1) + step) % PRESETS.len();
+                    diff = if PRESETS[i] == Preset::Custom { custom } else { Difficulty::preset(PRESETS[i]) };
+                }
+                if diff.preset == Preset::Custom {
+                    if is_key_pressed(KeyCode::Tab) { custom_field = (custom_field + 1) % diff.fields().len(); }
+                    if is_key_pressed(KeyCode::Equal) || is_key_pressed(KeyCode::KpAdd) { diff.adjust(custom_field, 0.1); }
+                    if is_key_pressed(KeyCode::Minus) || is_key_pressed(KeyCode::KpSubtract) { diff.adjust(custom_field, -0.1); }
+                    custom = diff;
+                }
+                if is_key_pressed(KeyCode::Down) || is_key_pressed(KeyCode::S) { selected = (selected + 1) % count; }
+                if is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::W) { selected = (selected + count - 1) % count; }
+                if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Space) {
+                    if selected == 1 {
+                        g = Game::daily(Daily::today()); template = g.arena.clone(); screens.set(State::Running); playtest = false;
+                    } else {
+                        let pick = if selected == 0 { Some(Arena::generate(arena::random_seed())) } else { maps[selected - 2].1.as_ref().ok().cloned() };
+                        if let Some(a) = pick { template = a; g = Game::new(template.clone(), mode, diff, coop, arena::random_seed()); screens.set(State::Running); playtest = false; }
+                    }
+                }
+                if is_key_pressed(KeyCode::E) {
+                    editor = match selected {
+                        0 => Some(Editor::new(Arena::empty("Custom", 20.0), map::fresh_file_name(map::MAP_DIR))),
+                        1 => None, // the daily layout isn't editable
+                        i => match &maps[i - 2] { (file, Ok(a)) => Some(Editor::new(a.clone(), file.clone())), (_, Err(_)) => None }, // fix broken files by hand first
+                    };
+                    if editor.is_some() { screens.set(State::Editor); }
+                }
+                if is_key_pressed(KeyCode::H) { (hs_mode, hs_diff) = (mode, diff.key()); screens.push(State::HighScores); }
+                if is_key_pressed(KeyCode::C) { screens.push(State::Achievements); }
+                if is_key_pressed(KeyCode::Escape) { screens.pop(); }
+            }
+            State::Achievements => {
+                ach.draw_screen();
+                if is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::C) { screens.pop(); }
+            }
+            State::HighScores => {
+                // Tab: next mode; ← / →: difficulty
+                if is_key_pressed(KeyCode::Tab) { hs_mode = MODES[(MODES.iter().position(|m| *m == hs_mode).unwrap_or(0) + 1) % MODES.len()]; }
+                let keys = scores.diff_keys();
+                let at = keys.iter().position(|k| *k == hs_diff).unwrap_or(0);
+                if is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::D) { hs_diff = keys[(at + 1) % keys.len()].clone(); }
+                if is_key_pressed(KeyCode::Left) || is_key_pressed(KeyCode::A) { hs_diff = keys[(at + keys.len() - 1) % keys.len()].clone(); }
+                draw_high_scores(&scores, hs_mode, &hs_diff);
+                if is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::H) { screens.pop(); }
+            }
+            State::Editor => {
+                let Some(ed) = editor.as_mut() else { screens.set(State::MapSelect); next_frame().await; continue; };
+                match ed.update() {
+                    EditorAction::None => {}
+                    EditorAction::Playtest => { template = ed.arena.clone(); g = Game::new(template.clone(), mode, diff, coop, arena::random_seed()); screens.set(State::Running); playtest = true; }
+                    EditorAction::Exit if ed.dirty() => { confirm_sel = 1; screens.push(State::ConfirmDiscard); }
+                    EditorAction::Exit => { maps = map::load_dir(map::MAP_DIR); screens.set(State::MapSelect); }
+                }
+                ed.draw();
+            }
+            State::ConfirmDiscard => {
+                if let Some(ed) = &editor { ed.draw(); }
+                match ui::confirm("Leave without saving?", &mut confirm_sel) {
+                    Some(true) => { maps = map::load_dir(map::MAP_DIR); screens.pop(); screens.set(State::MapSelect); }
+                    Some(false) => screens.pop(),
+                    None => {}
+                }
+            }
+            State::Running => {
+                // playtests go straight back to the editor instead of pausing
+                if bindings.pressed(Action::Pause) {
+                    if playtest { screens.set(State::Editor); } else { pause_sel = 0; screens.push(State::Pause); }
                 }
 
-                // wave cleared?
-                if zombies.is_empty() && spawn_budget_left == 0 { state = State::InterWave; inter_timer = 2.0; }
+                let mut inputs: Vec<PlayerInput> = g.coop.sources().into_iter().map(|src| bindings.player(src)).collect();
+                if let (Some(pin), Some(p2)) = (host.as_ref().and_then(|h| h.remote_input()), inputs.get_mut(1)) { *p2 = pin; }
+                if demo.is_some() { inputs[0] = bot.input(&g, 0, dt); }
+                match step(&mut g, dt, &inputs) {
+                    Step::Over => screens.set(State::GameOver),
+                    Step::WaveCleared => screens.set(State::InterWave),
+                    Step::Playing => {}
+                }
 
                 // render world
-                render_world(&player, &bullets, &zombies, &drops, &covers, shake);
-                draw_hud(score, player.hp, wave, combo, false);
+                render_world(&g, &fx, &settings);
+                draw_hud(&g, false, settings.hud_scale, &bindings);
+                if let Some(hint) = p2_hint(&g, host.as_ref()) { draw_text(&hint, 16.0, 102.0 * settings.hud_scale, 24.0 * settings.hud_scale, ORANGE); }
+            }
+            State::Remote => {
+                if let Some(c) = client.as_mut() {
+                    c.frame(dt, bindings.player(Source::All));
+                    match &c.game {
+                        None => ui::notice(&[if c.refused && c.watching { "No room to watch that run" } else if c.refused { "The host already has a player 2" } else if c.lost() { "Can't reach the host" } else { "Connecting to the host…" }, "Esc: back"]),
+                        Some(rg) if c.watching => {
+                            spectator.update(rg, dt);
+                            set_camera(&spectator.camera(rg));
+                            draw_scene(rg, &fx);
+                            set_default_camera();
+                            spectator.draw_overlay(rg);
+                            remote_notices(c, rg);
+                        }
+                        Some(rg) => {
+                            render_world(rg, &fx, &settings);
+                            draw_hud(rg, c.phase == net::Phase::Intermission, settings.hud_scale, &bindings);
+                            remote_notices(c, rg);
+                        }
+                    }
+                }
+                if client.is_none() || bindings.pressed(Action::Pause) {
+                    if let Some(c) = client.as_mut() { c.leave(); }
+                    client = None; screens.reset();
+                }
             }
             State::InterWave => {
-                inter_timer -= dt;
-                render_world(&player, &bullets, &zombies, &drops, &covers, shake);
-                draw_hud(score, player.hp, wave, combo, true);
-                let msg = format!("Wave {} cleared! Next in {:.1}s", wave, inter_timer.max(0.0));
+                if bindings.pressed(Action::Pause) && !playtest { pause_sel = 0; screens.push(State::Pause); }
+                render_world(&g, &fx, &settings);
+                draw_hud(&g, true, settings.hud_scale, &bindings);
+                let msg = format!("Wave {} cleared! Next in {:.1}s", g.wave, g.inter_timer.max(0.0));
                 let tw = measure_text(&msg, None, 36, 1.0);
                 draw_text(&msg, screen_width()*0.5 - tw.width*0.5, screen_height()*0.5, 36.0, YELLOW);
-                if inter_timer <= 0.0 {
-                    wave += 1; spawn_budget_left = wave_spawn_budget(wave); state = State::Running;
-                    // small heal each wave
-                    player.hp = (player.hp + 1).min(PLAYER_MAX_HP);
-                }
+                if intermission(&mut g, dt) { screens.set(State::Running); }
             }
             State::GameOver => {
-                set_default_camera();
-                let msg = "GAME OVER — Press R to restart";
-                let tw = measure_text(msg, None, 44, 1.0);
-                draw_text(msg, screen_width()*0.5 - tw.width*0.5, screen_height()*0.45, 44.0, RED);
-                draw_text(&format!("Final Score: {}", score), screen_width()*0.5 - 120.0, screen_height()*0.55, 28.0, WHITE);
-                if is_key_pressed(KeyCode::R) { // reset
-                    player = Player { pos: vec3(0.0, 0.5, 0.0), hp: PLAYER_MAX_HP, cd: 0.0, rapid_left: 0.0, slow_left: 0.0 };
-                    bullets.clear(); zombies.clear(); drops.clear();
-                    score = 0; combo = 1.0; combo_timer = 0.0; wave = 1; spawn_budget_left = wave_spawn_budget(wave); state = State::Running; shake = 0.0;
+                if !g.recorded {
+                    g.recorded = true; demo_timer = DEMO_RESULTS;
+                    if let Some(d) = &g.daily {
+                        let entry = daily::Entry { score: g.score, wave: g.wave, secs: g.elapsed as u32 };
+                        if let Err(e) = d.record(entry) { eprintln!("couldn't save the daily board: {}", e); }
+                        daily_board = d.board();
+                    }
+                    // playtests and bot runs don't count, nor do dailies (their modifiers skew the
+                    // score; they have their own board); a table-worthy score asks for a name first
+                    new_rank = None; show_stats = false; export_msg = None;
+                    if !playtest && demo.is_none() && g.daily.is_none() {
+                        // co-op totals aren't comparable with solo scores, so they only go in the history
+                        if scores.qualifies(g.score, g.mode.key(), &g.diff.key()) && g.coop == Coop::Solo { name_entry = Some(scores.last_name()); clear_input_queue(); }
+                        else { scores.add(run_record(&g, scores.last_name()), false); }
+                    }
+                }
+                if show_stats { draw_stats(&g); }
+                else {
+                    let best = if g.daily.is_some() { daily_board.iter().map(|e| e.score).max().unwrap_or(0) } else { scores.best(g.mode.key(), &g.diff.key()) };
+                    draw_results(&g, best.max(g.score), &format!("Press {} to restart", bindings.label(Action::Restart)));
+                    if let Some(d) = &g.daily { draw_daily_board(d, &daily_board, g.score); }
                 }
+                if let Some(name) = name_entry.as_mut() {
+                    let mut typed = vec![];
+                    while let Some(c) = get_char_pressed() { typed.push(c); }
+                    for c in typed.into_iter().rev() { // the queue pops newest first
+                        if !c.is_control() && name.chars().count() < scores::NAME_MAX { name.push(c); }
+                    }
+                    if is_key_pressed(KeyCode::Backspace) { name.pop(); }
+                    draw_name_entry(name);
+                    let save = is_key_pressed(KeyCode::Enter);
+                    if save || is_key_pressed(KeyCode::Escape) {
+                        let name = scores::clean_name(name);
+                        new_rank = scores.add(run_record(&g, name), save);
+                        name_entry = None;
+                    }
+                    ach.draw_toasts(dt);
+                    next_frame().await;
+                    continue;
+                }
+                if is_key_pressed(KeyCode::Tab) { show_stats = !show_stats; }
+                if is_key_pressed(KeyCode::X) {
+                    let map = match (&g.daily, g.arena.seed) { (Some(d), _) => format!("daily {}", d.date), (None, Some(seed)) => format!("seed {}", seed), (None, None) => g.arena.name.clone() };
+                    export_msg = Some(match g.stats.export(&run_record(&g, scores.last_name()), &map) {
+                        Ok(path) => format!("Stats exported to {} (+ runs.csv)", path.display()),
+                        Err(e) => format!("Export failed: {}", e),
+                    });
+                }
+                let hint = export_msg.as_deref().unwrap_or(if show_stats { "Tab summary  •  X export JSON/CSV" } else { "Tab run stats  •  X export JSON/CSV" });
+                draw_text(hint, 20.0, screen_height() - 18.0, 20.0, GRAY);
+                if let Some(r) = new_rank.filter(|_| !show_stats) {
+                    let text = format!("New high score — #{} on the {} • {} table (H on the map screen)", r + 1, g.mode.name(), g.diff.name());
+                    draw_text(&text, screen_width()*0.5 - 120.0, screen_height()*0.55 + 84.0, 22.0, GOLD);
+                }
+                let again = match (&g.daily, template.seed) {
+                    (Some(d), _) => format!("Daily {}  •  M maps", d.date),
+                    (None, Some(seed)) => format!("Arena seed: {}  •  {} retry it  •  M maps", seed, bindings.label(Action::RetryArena)),
+                    (None, None) => format!("Map: {}  •  M maps", template.name),
+                };
+                draw_text(&again, screen_width()*0.5 - 120.0, screen_height()*0.55 + 32.0, 22.0, GRAY);
+                if playtest { draw_text("E back to editor", screen_width()*0.5 - 120.0, screen_height()*0.55 + 58.0, 22.0, GRAY); }
+                if playtest && is_key_pressed(KeyCode::E) { screens.set(State::Editor); }
+                if is_key_pressed(KeyCode::M) { screens.set(State::MapSelect); }
+                let retry = bindings.pressed(Action::RetryArena);
+                if bindings.pressed(Action::Restart) || retry { g = restart(&g, &mut template, retry); screens.set(State::Running); }
+                // the bot goes again by itself (on the same arena if one was asked for)
+                demo_timer -= dt;
+                if demo.is_some() && demo_timer <= 0.0 { g = restart(&g, &mut template, seed_arg.is_some()); bot = Bot::new(); screens.set(State::Running); }
             }
         }
 
+        if let Some(d) = demo.filter(|_| in_run) { draw_demo_banner(d); }
+
+        // hand this frame's gameplay events to everything that reacts to them (the bot earns no achievements)
+        let batch = std::mem::take(&mut g.events);
+        if demo.is_some() { events::dispatch(&batch, &mut [&mut g.stats, &mut g.director, &mut fx, &mut log]); }
+        else { events::dispatch(&batch, &mut [&mut g.stats, &mut g.director, &mut ach, &mut fx, &mut log]); }
+
+        fx.draw_overlay();
+        ach.draw_toasts(dt);
         next_frame().await;
     }
+    ach.save(); // lifetime counters move mid-run too
 }
 
-fn render_world(player: &Player, bullets: &[Bullet], zombies: &[Zombie], drops: &[Powerup], covers: &[Vec3], shake: f32) {
-    // camera: chase w/ shake
-    let mut cam_pos = vec3(player.pos.x, 8.0, player.pos.z + 16.0);
-    let jitter = vec3((rand::gen_range(-1.0, 1.0))*0.15*shake, (rand::gen_range(-1.0, 1.0))*0.10*shake, (rand::gen_range(-1.0, 1.0))*0.2*shake);
-    cam_pos += jitter;
-    set_camera(&Camera3D { position: cam_pos, target: player.pos, up: vec3(0.0,1.0,0.0), fovy: 45.0, ..Default::default() });
+// anything at all from the player: wakes the title screen, ends the attract demo
+fn any_input() -> bool {
+    Binding::captured().is_some() || is_key_pressed(KeyCode::Escape) || mouse_delta_position() != Vec2::ZERO
+        || (0..gamepad::count()).any(|i| gamepad::left_stick_on(i) != Vec2::ZERO)
+}
 
-    // arena floor & bounds
-    draw_grid(40, 1.0, Color::from_rgba(30,32,40,255), Color::from_rgba(58,62,74,255));
-    // boundary walls (low)
-    for i in 0..4 { let rot = i as f32 * std::f32::consts::FRAC_PI_2; let dir = vec3(rot.sin(), 0.0, rot.cos());
-        let center = dir * ARENA_HALF; draw_cube(center + vec3(0.0, 0.5, 0.0), vec3(ARENA_HALF*2.0, 1.0, 0.6), None, Color::from_rgba(46,50,64,255)); }
+fn draw_demo_banner(demo: Demo) {
+    let text = match demo { Demo::Attract => "DEMO  •  press any key", Demo::Soak => "AUTO-PLAY  •  the bot is player 1" };
+    let tw = measure_text(text, None, 26, 1.0);
+    draw_text(text, screen_width()*0.5 - tw.width*0.5, 34.0, 26.0, ORANGE);
+}
 
-    // covers
-    for c in covers { draw_cube(*c, vec3(1.4, 1.2, 1.4), None, Color::from_rgba(64,66,86,255)); }
+// what's missing for player 2 to play, if anything
+fn p2_hint(g: &Game, host: Option<&net::Host>) -> Option<String> {
+    if g.coop == Coop::Solo { return None; }
+    match host {
+        Some(h) if !h.connected() => Some("Player 2: waiting for someone to join".to_owned()),
+        Some(_) => None,
+        None if !gamepad::SUPPORTED => Some("Player 2: no controllers on this platform; use --host to play online".to_owned()),
+        None if gamepad::count() == 0 => Some("Player 2: connect a controller".to_owned()),
+        None => None,
+    }
+}
 
-    // player
-    draw_cube(player.pos, vec3(1.0, 1.0, 1.0), None, SKYBLUE);
+// what a networked guest should see of the run on our screen stack, None outside one
+fn net_phase(screens: &Screens) -> Option<net::Phase> {
+    let run = screens.0.iter().rposition(|s| matches!(s, State::Running | State::InterWave | State::GameOver))?;
+    if run + 1 < screens.0.len() { return Some(net::Phase::Paused); }
+    Some(match screens.0[run] { State::Running => net::Phase::Playing, State::InterWave => net::Phase::Intermission, _ => net::Phase::Over })
+}
+
+fn draw_map_select(maps: &[(String, Result<Arena, String>)], selected: usize, mode: Mode, diff: &Difficulty, custom_field: usize, coop: Coop, best: u32) {
+    set_default_camera();
+    let title = "SELECT MAP";
+    let tw = measure_text(title, None, 44, 1.0);
+    draw_text(title, screen_width()*0.5 - tw.width*0.5, 90.0, 44.0, YELLOW);
+    let x = screen_width()*0.5 - 220.0;
+    let mut y = 160.0;
+    let mut row = |i: usize, label: &str, col: Color| {
+        if i == selected { draw_rectangle(x - 12.0, y - 24.0, 460.0, 34.0, Color::from_rgba(40,44,60,255)); }
+        draw_text(label, x, y, 26.0, col); y += 40.0;
+    };
+    row(0, "Random arena (new seed)", WHITE);
+    let today = Daily::today();
+    let mods: Vec<&str> = today.mods.iter().map(|m| m.name()).collect();
+    row(1, &format!("Daily challenge {}: {}  (Waves, Normal)", today.date, mods.join(" + ")), GOLD);
+    for (i, (file, res)) in maps.iter().enumerate() {
+        match res {
+            Ok(a) => row(i + 2, &format!("{}  ({})", a.name, file), WHITE),
+            Err(e) => row(i + 2, &format!("{}: {}", file, e), RED),
+        }
+    }
+    // mode + difficulty pickers, plus the multipliers when on Custom
+    y += 20.0;
+    draw_text(&format!("Mode:  {}", mode.name()), x, y, 28.0, YELLOW);
+    draw_text(mode.blurb(), x + 16.0, y + 22.0, 20.0, GRAY);
+    y += 56.0;
+    draw_text(&format!("Difficulty:  <  {}  >", diff.name()), x, y, 28.0, YELLOW);
+    // each difficulty keeps its own scores, so its best is the one to beat
+    if best > 0 { draw_text(&format!("best {}", best), x + 16.0, y + 22.0, 20.0, GRAY); y += 22.0; }
+    y += 34.0;
+    draw_text(&format!("Players:  {}", coop.name()), x, y, 28.0, YELLOW);
+    if coop != Coop::Solo { draw_text("player 2 uses a controller; the daily challenge is always solo", x + 16.0, y + 22.0, 20.0, GRAY); y += 22.0; }
+    if diff.preset == Preset::Custom {
+        for (i, (label, v)) in diff.fields().iter().enumerate() {
+            y += 26.0;
+            let col = if i == custom_field { WHITE } else { GRAY };
+            draw_text(&format!("{} {:<18} x{:.2}", if i == custom_field { ">" } else { " " }, label, v), x + 16.0, y, 22.0, col);
+        }
+    }
+    let info = "W/S or ↑/↓ select • A/D or ←/→ difficulty • Q mode • 2 players • Tab/+/- custom rules • Enter play • E edit (Random: new map) • H high scores • C achievements • Esc back";
+    let t = measure_text(info, None, 20, 1.0);
+    draw_text(info, screen_width()*0.5 - t.width*0.5, screen_height() - 18.0, 20.0, GRAY);
+}
+
+// what a network client (player or spectator) shows over the run for its phase
+fn remote_notices(c: &net::Client, rg: &Game) {
+    match c.phase {
+        net::Phase::Over => draw_results(rg, rg.score, if c.dedicated { "Next run starts shortly" } else { "Press R on the host to restart" }),
+        net::Phase::Intermission => {
+            let msg = format!("Wave {} cleared! Next in {:.1}s", rg.wave, rg.inter_timer.max(0.0));
+            let tw = measure_text(&msg, None, 36, 1.0);
+            draw_text(&msg, screen_width()*0.5 - tw.width*0.5, screen_height()*0.5, 36.0, YELLOW);
+        }
+        net::Phase::Paused => ui::notice(&["The host paused the game"]),
+        net::Phase::Lobby => ui::notice(&["Waiting for more players", "the run starts once the lobby is full"]),
+        net::Phase::Playing => {}
+    }
+    if c.lost() { ui::notice(&["Lost the connection to the host", "Esc: back"]); }
+}
+
+fn render_world(g: &Game, fx: &Fx, settings: &Settings) {
+    // camera: chase w/ shake, framing every player (coop.rs), or one half-screen camera each
+    let camera = |focus: Vec3, zoom: f32, viewport: Option<(i32, i32, i32, i32)>| Camera3D {
+        position: focus + vec3(0.0, 8.0, 16.0) * settings.camera_distance * zoom + fx.jitter(), target: focus, up: vec3(0.0,1.0,0.0),
+        fovy: settings.fov.to_radians(), aspect: viewport.map(|v| v.2 as f32 / v.3 as f32), viewport, ..Default::default()
+    };
+    let positions: Vec<Vec3> = g.players.iter().map(|p| p.pos).collect();
+    match coop::view(&positions) {
+        View::Shared { focus, zoom } => { set_camera(&camera(focus, zoom, None)); draw_scene(g, fx); }
+        View::Split(focus) => {
+            let (w, h) = (screen_width() as i32 / 2, screen_height() as i32);
+            for (i, f) in focus.into_iter().enumerate() { set_camera(&camera(f, 1.0, Some((i as i32 * w, 0, w, h)))); draw_scene(g, fx); }
+            set_default_camera();
+            draw_line(w as f32, 0.0, w as f32, h as f32, 3.0, BLACK);
+        }
+    }
+    set_default_camera();
+}
+
+fn draw_scene(g: &Game, fx: &Fx) {
+    let (bullets, zombies, drops, arena) = (&g.bullets, &g.zombies, &g.drops, &g.arena);
+    draw_arena(arena);
+    if let Some(o) = &g.objective { o.draw(); }
+
+    // players; downed ones lie flat with their revive progress as a ring
+    for (i, p) in g.players.iter().enumerate() {
+        let col = if g.players.len() > 1 { coop::COLORS[i % coop::COLORS.len()] } else { SKYBLUE };
+        if !p.downed { draw_cube(p.pos, vec3(1.0, 1.0, 1.0), None, col); continue; }
+        draw_cube(vec3(p.pos.x, 0.2, p.pos.z), vec3(1.0, 0.4, 1.0), None, Color::new(col.r * 0.5, col.g * 0.5, col.b * 0.5, 1.0));
+        let r = coop::REVIVE_RADIUS * p.revive / coop::REVIVE_TIME;
+        draw_cylinder(vec3(p.pos.x, 0.0, p.pos.z), coop::REVIVE_RADIUS, coop::REVIVE_RADIUS, 0.03, None, Color::new(1.0, 1.0, 1.0, 0.15));
+        draw_cylinder(vec3(p.pos.x, 0.01, p.pos.z), r, r, 0.04, None, Color::new(col.r, col.g, col.b, 0.6));
+    }
     // bullets
     for b in bullets { draw_sphere(b.pos, BULLET_RADIUS, None, YELLOW); }
     // zombies
     for z in zombies {
         let (col, s) = match z.kind { ZKind::Normal => (Color::from_rgba(40,180,90,255), vec3(1.1, 1.5, 1.1)), ZKind::Fast => (Color::from_rgba(60,220,120,255), vec3(0.9, 1.2, 0.9)), ZKind::Tank => (Color::from_rgba(30,140,70,255), vec3(1.4, 1.9, 1.4)) };
+        if z.rise > 0.0 {
+            // telegraph: pulsing ground marker, body grows up out of it
+            let t = 1.0 - z.rise / SPAWN_TELEGRAPH;
+            let pulse = 0.5 + 0.5 * (get_time() as f32 * 14.0).sin();
+            draw_cylinder(vec3(z.pos.x, 0.0, z.pos.z), zombie_radius(z.kind) * 1.7, zombie_radius(z.kind) * 1.7, 0.04, None, Color::new(0.85, 0.15, 0.1, 0.35 + 0.35 * pulse));
+            let h = s.y * t;
+            draw_cube(vec3(z.pos.x, z.pos.y + 0.2 - s.y * 0.5 + h * 0.5, z.pos.z), vec3(s.x, h, s.z), None, col);
+            continue;
+        }
         draw_cube(z.pos + vec3(0.0, 0.2, 0.0), s, None, col);
     }
+    fx.draw();
     // powerups
     for d in drops { let col = match d.kind { Drop::Heal=>PINK, Drop::Bomb=>ORANGE, Drop::Rapid=>SKYBLUE, Drop::Slow=>VIOLET }; draw_sphere(d.pos + vec3(0.0,0.5,0.0), 0.35, None, col); }
+}
 
+// per-mode results screen; `again` says how the next run starts
+fn draw_results(g: &Game, best: u32, again: &str) {
     set_default_camera();
+    let (title, col) = match g.outcome {
+        Some(Outcome::TimeUp) => ("TIME'S UP", YELLOW),
+        Some(Outcome::Complete) => ("MISSION COMPLETE", LIME),
+        Some(Outcome::Failed) => ("MISSION FAILED", RED),
+        _ => ("GAME OVER", RED),
+    };
+    let title = format!("{} — {}", title, again);
+    let tw = measure_text(&title, None, 44, 1.0);
+    draw_text(&title, screen_width()*0.5 - tw.width*0.5, screen_height()*0.40, 44.0, col);
+    let summary = match g.mode {
+        Mode::Waves | Mode::Hardcore => format!("{}: fell on wave {}", g.mode.name(), g.wave),
+        Mode::Endless => format!("Endless: survived {}", clock(g.elapsed)),
+        Mode::TimeAttack if g.outcome == Some(Outcome::TimeUp) => "Time Attack: made it to the buzzer".to_owned(),
+        Mode::TimeAttack => format!("Time Attack: died with {} left", clock(mode::TIME_ATTACK_LIMIT - g.elapsed)),
+        _ if g.outcome == Some(Outcome::Complete) => format!("{}: done in {}", g.mode.name(), clock(g.elapsed)),
+        _ => format!("{}: {}", g.mode.name(), g.objective.as_ref().map_or(String::new(), |o| o.hud_line())),
+    };
+    draw_text(&summary, screen_width()*0.5 - 120.0, screen_height()*0.48, 26.0, LIGHTGRAY);
+    draw_text(&format!("Final Score: {}  ({}, best {})", g.score, g.diff.name(), best), screen_width()*0.5 - 120.0, screen_height()*0.55, 28.0, WHITE);
+    if g.coop == Coop::Separate {
+        let split: Vec<String> = g.players.iter().enumerate().map(|(i, p)| format!("P{} {}", i + 1, p.score)).collect();
+        draw_text(&split.join("   "), screen_width()*0.5 + 260.0, screen_height()*0.55, 28.0, GOLD);
+    }
 }
 
-fn draw_hud(score: u32, hp: i32, wave: u32, combo: f32, paused: bool) {
-    let hud = format!("Score: {}    HP: {}    Wave: {}    Combo: x{:.1}{}", score, hp.max(0), wave, combo, if paused { "  [Intermission]" } else { "" });
-    draw_text(&hud, 16.0, 28.0, 28.0, WHITE);
-    let info = "WASD move • Shift sprint • LMB shoot • R restart";
-    let t = measure_text(info, None, 20, 1.0);
-    draw_text(info, screen_width()*0.5 - t.width*0.5, screen_height() - 18.0, 20.0, GRAY);
+// the GameOver stats page
+fn draw_stats(g: &Game) {
+    set_default_camera();
+    let st = &g.stats;
+    let title = "RUN STATS";
+    let tw = measure_text(title, None, 44, 1.0);
+    draw_text(title, screen_width()*0.5 - tw.width*0.5, 80.0, 44.0, YELLOW);
+    let (x, mut y) = (screen_width()*0.5 - 300.0, 130.0);
+    let per = |names: &[&str], v: &[u32]| names.iter().zip(v).map(|(n, c)| format!("{} {}", n, c)).collect::<Vec<_>>().join("  •  ");
+    let kills: u32 = st.kills.iter().sum();
+    let lines = [
+        format!("Score {}  •  {}  •  {} / {}", g.score, clock(g.elapsed), g.mode.name(), g.diff.name()),
+        format!("Shots {}  •  hits {}  •  accuracy {:.0}%", st.shots, st.hits, st.accuracy() * 100.0),
+        format!("Kills {}: {}  (bombs {})", kills, per(&stats::KIND_NAMES, &st.kills), st.bomb_kills),
+        format!("Damage taken {}: {}", st.damage.iter().sum::<u32>(), per(&stats::KIND_NAMES, &st.damage)),
+        format!("Powerups: {}", per(&stats::DROP_NAMES, &st.powerups)),
+        format!("Max combo x{:.2}  •  distance moved {:.0} m", st.max_combo.max(1.0), st.distance),
+    ];
+    for l in &lines { draw_text(l, x, y, 24.0, WHITE); y += 32.0; }
+    if g.mode.has_waves() {
+        y += 10.0;
+        draw_text("Time per wave", x, y, 24.0, YELLOW);
+        let mut row = String::new();
+        for (i, t) in st.wave_times.iter().enumerate() { row += &format!("{}: {}   ", i + 1, clock(*t)); }
+        row += &format!("{}: {} (fell)", st.wave_times.len() + 1, clock(st.current_wave(g.elapsed)));
+        // wrap every 6 waves
+        for chunk in row.split("   ").collect::<Vec<_>>().chunks(6) { y += 26.0; draw_text(&chunk.join("   "), x + 16.0, y, 20.0, LIGHTGRAY); }
+    }
+}
+
+fn draw_name_entry(name: &str) {
+    let (x, y) = (screen_width()*0.5 - 120.0, screen_height()*0.55 + 40.0);
+    draw_text("NEW HIGH SCORE!  Enter your name:", x, y, 26.0, GOLD);
+    let cursor = if (get_time() * 2.0) as i64 % 2 == 0 { "_" } else { " " };
+    draw_rectangle(x - 6.0, y + 12.0, 280.0, 34.0, Color::from_rgba(40,44,60,255));
+    draw_text(&format!("{}{}", name, cursor), x, y + 36.0, 28.0, WHITE);
+    draw_text("Enter save  •  Esc skip", x, y + 70.0, 20.0, GRAY);
 }
 
-fn spawn_zombie(wave: u32, center: Vec3) -> Zombie {
-    let angle = rand::gen_range(0.0, 360.0f32).to_radians();
-    let r = rand::gen_range(14.0, ARENA_HALF - 1.5);
-    let pos = vec3(center.x + angle.sin()*r, 0.5, center.z + angle.cos()*r);
-    // choose type weighted by wave
-    let roll = rand::gen_range(0.0, 1.0);
-    if roll < (0.15 + wave as f32 * 0.01).min(0.35) { // tank chance grows
-        Zombie { pos, speed: 1.6, kind: ZKind::Tank, hp: 3 }
-    } else if roll < 0.55 { // fast
-        Zombie { pos, speed: 3.6 + wave as f32 * 0.05, kind: ZKind::Fast, hp: 1 }
-    } else { // normal
-        Zombie { pos, speed: 2.4 + wave as f32 * 0.03, kind: ZKind::Normal, hp: 2 }
+fn draw_high_scores(scores: &Scores, mode: Mode, diff: &str) {
+    set_default_camera();
+    let title = "HIGH SCORES";
+    let tw = measure_text(title, None, 44, 1.0);
+    draw_text(title, screen_width()*0.5 - tw.width*0.5, 80.0, 44.0, YELLOW);
+    // presets read by name; custom rule sets by their key
+    let diff_name = |k: &str| PRESETS.iter().map(|p| Difficulty::preset(*p)).find(|d| d.preset != Preset::Custom && d.key() == k).map_or_else(|| k.to_owned(), |d| d.name().to_owned());
+    let sub = format!("<  {}  •  {}  >", mode.name(), diff_name(diff));
+    let sw = measure_text(&sub, None, 26, 1.0);
+    draw_text(&sub, screen_width()*0.5 - sw.width*0.5, 116.0, 26.0, WHITE);
+    let x = screen_width()*0.5 - 330.0;
+    let mut y = 156.0;
+    let mode_name = |k: &str| Mode::from_key(k).map_or_else(|| k.to_owned(), |m| m.name().to_owned());
+    let row = |r: &scores::Record| format!("{:<12} {:>7}  wave {:<3} {:<22} {:<10} {:>6}  {}", r.name, r.score, r.wave, mode_name(&r.mode), diff_name(&r.diff), clock(r.secs as f32), r.date);
+    draw_text(&format!("    {:<12} {:>7}  {:<8} {:<22} {:<10} {:>6}  {}", "Name", "Score", "Reached", "Mode", "Difficulty", "Time", "Date"), x, y, 20.0, GRAY);
+    let table = scores.table(mode.key(), diff);
+    if table.is_empty() { y += 30.0; draw_text("No scores here yet — go make some.", x, y, 24.0, LIGHTGRAY); }
+    for (i, r) in table.iter().enumerate() {
+        y += 28.0;
+        draw_text(&format!("{:>2}. {}", i + 1, row(r)), x, y, 22.0, if i == 0 { GOLD } else { WHITE });
+    }
+    y += 50.0;
+    draw_text(&format!("Recent runs ({} played)", scores.history.len()), x, y, 26.0, YELLOW);
+    for r in scores.history.iter().rev().take(8) {
+        y += 24.0;
+        draw_text(&format!("    {}", row(r)), x, y, 20.0, LIGHTGRAY);
     }
+    for w in &scores.warnings { y += 26.0; draw_text(w, x, y, 20.0, ORANGE); }
+    let info = "Tab mode  •  ← / → difficulty  •  Esc / H back";
+    let t = measure_text(info, None, 20, 1.0);
+    draw_text(info, screen_width()*0.5 - t.width*0.5, screen_height() - 18.0, 20.0, GRAY);
 }
 
-fn maybe_drop(out: &mut Vec<Powerup>, pos: Vec3) {
-    let p = rand::gen_range(0.0, 1.0);
-    if p < 0.22 {
-        let kind = if p < 0.07 { Drop::Heal } else if p < 0.12 { Drop::Bomb } else if p < 0.18 { Drop::Rapid } else { Drop::Slow };
-        out.push(Powerup { pos, kind, ttl: 12.0 });
+fn draw_daily_board(d: &Daily, board: &[daily::Entry], score: u32) {
+    let x = screen_width()*0.5 - 120.0;
+    let mut y = screen_height()*0.55 + 96.0;
+    draw_text(&format!("Daily board {}", d.date), x, y, 24.0, GOLD);
+    let mut marked = false;
+    for (i, e) in board.iter().take(5).enumerate() {
+        y += 24.0;
+        let mine = !marked && e.score == score;
+        marked |= mine;
+        draw_text(&format!("{:>2}. {:>6}   wave {:<3} {}", i + 1, e.score, e.wave, clock(e.secs as f32)), x, y, 22.0, if mine { YELLOW } else { LIGHTGRAY });
     }
 }
 
-fn apply_powerup(player: &mut Player, kind: Drop, zombies: &mut Vec<Zombie>, score: &mut u32) {
-    match kind {
-        Drop::Heal => { player.hp = (player.hp + 2).min(PLAYER_MAX_HP); }
-        Drop::Bomb => {
-            let mut killed = 0u32; let radius = 4.2;
-            let mut i=0; while i < zombies.len() { if (zombies[i].pos - player.pos).length() <= radius { zombies.swap_remove(i); killed+=1; } else { i+=1; } }
-            *score += killed * 15;
+fn draw_arena(arena: &Arena) {
+    // arena floor & bounds
+    draw_grid((arena.half * 2.0) as u32, 1.0, Color::from_rgba(30,32,40,255), Color::from_rgba(58,62,74,255));
+    // boundary walls (low)
+    for i in 0..4 { let rot = i as f32 * std::f32::consts::FRAC_PI_2; let dir = vec3(rot.sin(), 0.0, rot.cos());
+        let size = if i % 2 == 0 { vec3(arena.half*2.0, 1.0, 0.6) } else { vec3(0.6, 1.0, arena.half*2.0) };
+        let center = dir * arena.half; draw_cube(center + vec3(0.0, 0.5, 0.0), size, None, Color::from_rgba(46,50,64,255)); }
+    for w in &arena.walls { draw_cube(w.pos, w.size, None, Color::from_rgba(46,50,64,255)); draw_cube_wires(w.pos, w.size, Color::from_rgba(30,32,42,255)); }
+
+    // covers: intact -> cracked -> broken rubble as hp drops
+    for c in &arena.covers {
+        let frac = c.hp / c.max_hp;
+        if frac > 0.66 {
+            draw_cube(c.pos, c.size, None, Color::from_rgba(64,66,86,255));
+        } else if frac > 0.33 {
+            draw_cube(c.pos, c.size, None, Color::from_rgba(56,52,62,255));
+            draw_cube_wires(c.pos, c.size * 1.01, Color::from_rgba(24,22,28,255));
+        } else {
+            let s = vec3(c.size.x, c.size.y * 0.45, c.size.z);
+            draw_cube(vec3(c.pos.x, s.y * 0.5, c.pos.z), s, None, Color::from_rgba(48,40,40,255));
+            draw_cube(vec3(c.pos.x + c.size.x * 0.3, 0.15, c.pos.z - c.size.z * 0.35), vec3(0.4, 0.3, 0.4), None, Color::from_rgba(48,40,40,255));
         }
-        Drop::Rapid => { player.rapid_left = 6.0; }
-        Drop::Slow => { player.slow_left = 6.0; }
     }
 }
 
-fn in_bounds(p: Vec3) -> bool { p.x.abs() <= ARENA_HALF+2.0 && p.z.abs() <= ARENA_HALF+2.0 }
-
-fn wave_spawn_budget(wave: u32) -> usize { (8 + (wave as usize)*5).min(120) }
\ No newline at end of file
+fn draw_hud(g: &Game, intermission: bool, scale: f32, bindings: &Bindings) {
+    // waves show the wave number; the continuous modes show the clock instead
+    let progress = match g.mode.time_limit() {
+        Some(limit) => format!("Time left: {}", clock(limit - g.elapsed)),
+        None if g.mode.has_waves() => format!("Wave: {}", g.wave),
+        None => format!("Time: {}", clock(g.elapsed)),
+    };
+    // co-op: each player's HP (or revive progress), and their own score when kept separate
+    let hp = if g.players.len() == 1 { format!("HP: {}", g.players[0].hp.max(0)) } else {
+        g.players.iter().enumerate().map(|(i, p)| {
+            let state = if p.downed { format!("DOWN {:.0}%", p.revive / coop::REVIVE_TIME * 100.0) } else { format!("HP {}", p.hp) };
+            if g.coop == Coop::Separate { format!("P{} {} pts {}", i + 1, p.score, state) } else { format!("P{} {}", i + 1, state) }
+        }).collect::<Vec<_>>().join("  ")
+    };
+    let hud = format!("{}: {}    {}    {}    Combo: x{:.1}{}", if g.coop == Coop::Separate { "Team" } else { "Score" }, g.score, hp, progress, g.combo, if intermission { "  [Intermission]" } else { "" });
+    draw_text(&hud, 16.0, 28.0 * scale, 28.0 * scale, WHITE);
+    let (arena, diff) = (&g.arena, &g.diff);
+    let map = if let Some(d) = &g.daily {
+        format!("Daily {}  •  {}", d.date, d.mods.iter().map(|m| m.name()).collect::<Vec<_>>().join(" + "))
+    } else { match arena.seed { Some(seed) => format!("Arena seed: {}  •  {} / {}", seed, g.mode.name(), diff.name()), None => format!("Map: {}  •  {} / {}", arena.name, g.mode.name(), diff.name()) } };
+    draw_text(&map, 16.0, 50.0 * scale, 18.0 * scale, GRAY);
+    if let Some(o) = &g.objective { draw_text(&o.hud_line(), 16.0, 76.0 * scale, 24.0 * scale, YELLOW); }
+    let k = |a: Action| bindings.label(a);
+    let info = format!("{}{}{}{} move • {} sprint • {} shoot • {} pause", k(Action::MoveUp), k(Action::MoveLeft), k(Action::MoveDown), k(Action::MoveRight), k(Action::Sprint), k(Action::Fire), k(Action::Pause));
+    let t = measure_text(&info, None, (20.0 * scale) as u16, 1.0);
+    draw_text(&info, screen_width()*0.5 - t.width*0.5, screen_height() - 18.0 * scale, 20.0 * scale, GRAY);
+}
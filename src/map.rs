@@ -0,0 +1,188 @@
+use macroquad::prelude::*;
+
+use crate::arena::{Arena, Cover, SpawnZone, Wall, COVER_HP};
+
+// ---- Map files: plain text, one entry per line, '#' starts a comment ----
+// name <text>                              shown on the map select screen
+// bounds <half>                            arena spans -half..+half on x and z
+// spawn <x> <z>                            player start
+// wall <x0> <z0> <x1> <z1> [thickness]     indestructible, axis-aligned segment
+// cover <x> <z> <w> <h> <d> [hp]           destructible box
+// zone <x> <z> <radius>                    zombie spawn zone
+// pickup <x> <z>                           powerup spawn point
+// -------------------------------------------------------------------------
+
+pub const MAP_DIR: &str = "maps";
+
+const MIN_HALF: f32 = 8.0;
+const MAX_HALF: f32 = 64.0;
+
+pub fn parse(src: &str) -> Result<Arena, String> {
+    let mut a = Arena::empty("Untitled", 0.0);
+    for (ln, raw) in src.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or("").trim();
+        if line.is_empty() { continue; }
+        let (key, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let err = |msg: &str| format!("line {}: {}", ln + 1, msg);
+        if key == "name" { a.name = rest.trim().to_owned(); continue; }
+        let nums: Vec<f32> = rest.split_whitespace().map(|t| t.parse::<f32>()).collect::<Result<_, _>>().map_err(|_| err("expected numbers"))?;
+        let want = |lo: usize, hi: usize| if nums.len() < lo || nums.len() > hi { Err(err(&format!("`{}` takes {}..{} numbers, got {}", key, lo, hi, nums.len()))) } else { Ok(()) };
+        match key {
+            "bounds" => { want(1, 1)?; a.half = nums[0]; }
+            "spawn" => { want(2, 2)?; a.spawn = vec2(nums[0], nums[1]); }
+            "wall" => {
+                want(4, 5)?;
+                let (p0, p1) = (vec2(nums[0], nums[1]), vec2(nums[2], nums[3]));
+                if p0.x != p1.x && p0.y != p1.y { return Err(err("walls must be axis-aligned")); }
+                if p0 == p1 { return Err(err("wall has zero length")); }
+                a.walls.push(Wall::from_segment(p0, p1, nums.get(4).copied().unwrap_or(0.6)));
+            }
+            "cover" => {
+                want(5, 6)?;
+                let size = vec3(nums[2], nums[3], nums[4]);
+                if size.min_element() <= 0.0 { return Err(err("cover size must be positive")); }
+                let hp = nums.get(5).copied().unwrap_or(COVER_HP);
+                if hp <= 0.0 { return Err(err("cover hp must be positive")); }
+                a.covers.push(Cover::new(nums[0], nums[1], size, hp));
+            }
+            "zone" => {
+                want(3, 3)?;
+                if nums[2] <= 0.0 { return Err(err("zone radius must be positive")); }
+                a.zones.push(SpawnZone { pos: vec2(nums[0], nums[1]), radius: nums[2] });
+            }
+            "pickup" => { want(2, 2)?; a.pickups.push(vec2(nums[0], nums[1])); }
+            _ => return Err(err(&format!("unknown entry `{}`", key))),
+        }
+    }
+    validate(&a)?;
+    Ok(a)
+}
+
+fn validate(a: &Arena) -> Result<(), String> {
+    if !(MIN_HALF..=MAX_HALF).contains(&a.half) { return Err(format!("bounds must be between {} and {}", MIN_HALF, MAX_HALF)); }
+    // `pad`: half the thing's extent on each axis
+    let inside = |p: Vec2, pad: Vec2| p.x.abs() + pad.x <= a.half && p.y.abs() + pad.y <= a.half;
+    if !inside(a.spawn, Vec2::splat(1.0)) { return Err("spawn is outside the arena".to_owned()); }
+    for (i, w) in a.walls.iter().enumerate() {
+        if !inside(vec2(w.pos.x, w.pos.z), vec2(w.size.x, w.size.z) * 0.5) { return Err(format!("wall {} is outside the arena", i + 1)); }
+    }
+    for (i, c) in a.covers.iter().enumerate() {
+        let p = vec2(c.pos.x, c.pos.z);
+        if !inside(p, vec2(c.size.x, c.size.z) * 0.5) { return Err(format!("cover {} is outside the arena", i + 1)); }
+        if (p - a.spawn).abs().cmplt(vec2(c.size.x, c.size.z) * 0.5 + 1.0).all() { return Err(format!("cover {} blocks the player spawn", i + 1)); }
+    }
+    for (i, z) in a.zones.iter().enumerate() {
+        if !inside(z.pos, Vec2::splat(z.radius)) { return Err(format!("zone {} is outside the arena", i + 1)); }
+    }
+    for (i, p) in a.pickups.iter().enumerate() {
+        if !inside(*p, Vec2::splat(0.5)) { return Err(format!("pickup {} is outside the arena", i + 1)); }
+    }
+    if !a.is_connected() { return Err("part of the floor is walled off from the spawn".to_owned()); }
+    Ok(())
+}
+
+// every *.map in the directory, `default.map` first; broken files are kept with their error
+pub fn load_dir(dir: &str) -> Vec<(String, Result<Arena, String>)> {
+    let mut out = vec![];
+    let Ok(entries) = std::fs::read_dir(dir) else { return out; };
+    for e in entries.flatten() {
+        let path = e.path();
+        if path.extension().and_then(|x| x.to_str()) != Some("map") { continue; }
+        let file = path.file_name().map(|f| f.to_string_lossy().into_owned()).unwrap_or_default();
+        let res = std::fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|s| parse(&s));
+        out.push((file, res));
+    }
+    out.sort_by(|a, b| (a.0 != "default.map", &a.0).cmp(&(b.0 != "default.map", &b.0)));
+    out
+}
+
+pub fn to_string(a: &Arena) -> String {
+    let mut out = format!("name {}\nbounds {}\nspawn {} {}\n", a.name, a.half, a.spawn.x, a.spawn.y);
+    if !a.walls.is_empty() { out.push('\n'); }
+    for w in &a.walls {
+        // back to a centre-line segment; the thin side is the thickness
+        let t = w.size.x.min(w.size.z);
+        let h = vec2(w.size.x - t, w.size.z - t) * 0.5;
+        out += &format!("wall {} {} {} {} {}\n", w.pos.x - h.x, w.pos.z - h.y, w.pos.x + h.x, w.pos.z + h.y, t);
+    }
+    if !a.covers.is_empty() { out.push('\n'); }
+    for c in &a.covers {
+        out += &format!("cover {} {} {} {} {}", c.pos.x, c.pos.z, c.size.x, c.size.y, c.size.z);
+        if c.max_hp != COVER_HP { out += &format!(" {}", c.max_hp); }
+        out.push('\n');
+    }
+    if !a.zones.is_empty() { out.push('\n'); }
+    for z in &a.zones { out += &format!("zone {} {} {}\n", z.pos.x, z.pos.y, z.radius); }
+    if !a.pickups.is_empty() { out.push('\n'); }
+    for p in &a.pickups { out += &format!("pickup {} {}\n", p.x, p.y); }
+    out
+}
+
+// first `custom<n>.map` that doesn't exist yet
+pub fn fresh_file_name(dir: &str) -> String {
+    (1..).map(|n| format!("custom{}.map", n)).find(|f| !std::path::Path::new(dir).join(f).exists()).unwrap_or_default()
+}
+
+pub fn save(dir: &str, file: &str, a: &Arena) -> Result<(), String> {
+    let text = to_string(a);
+    parse(&text)?; // never write a map we couldn't load back
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    std::fs::write(std::path::Path::new(dir).join(file), text).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "name Test yard  # a comment\nbounds 16\nspawn 0 4\n\nwall -6 -2 6 -2 0.8\ncover 3 0 2 1.2 1 40\ncover -3 0 2 1.2 1\nzone -10 -10 2.5\npickup 5 5\n";
+
+    #[test]
+    fn parses_every_entry() {
+        let a = parse(SAMPLE).unwrap();
+        assert_eq!(a.name, "Test yard");
+        assert_eq!((a.half, a.spawn), (16.0, vec2(0.0, 4.0)));
+        assert_eq!((a.walls.len(), a.covers.len(), a.zones.len(), a.pickups.len()), (1, 2, 1, 1));
+        assert_eq!(a.walls[0].size, vec3(12.8, a.walls[0].size.y, 0.8));
+        assert_eq!((a.covers[0].max_hp, a.covers[1].max_hp), (40.0, COVER_HP));
+        assert_eq!(a.zones[0].radius, 2.5);
+    }
+
+    #[test]
+    fn to_string_round_trips() {
+        let text = to_string(&parse(SAMPLE).unwrap());
+        assert_eq!(to_string(&parse(&text).unwrap()), text);
+        for seed in [1, 42, 9001] {
+            let text = to_string(&Arena::generate(seed));
+            assert_eq!(to_string(&parse(&text).unwrap_or_else(|e| panic!("seed {}: {}", seed, e))), text);
+        }
+    }
+
+    #[test]
+    fn shipped_maps_load() {
+        for (file, res) in load_dir(MAP_DIR) { assert!(res.is_ok(), "{}: {:?}", file, res.err()); }
+    }
+
+    #[test]
+    fn rejects_bad_lines_with_their_number() {
+        let err = |src: &str| parse(src).err().unwrap_or_default();
+        assert_eq!(err("bounds 16\nspawn 0 0\nfence 1 2"), "line 3: unknown entry `fence`");
+        assert_eq!(err("bounds 16\nspawn x 0"), "line 2: expected numbers");
+        assert_eq!(err("bounds 16 2"), "line 1: `bounds` takes 1..1 numbers, got 2");
+        assert_eq!(err("bounds 16\nwall 0 0 3 3"), "line 2: walls must be axis-aligned");
+        assert_eq!(err("bounds 16\nwall 1 1 1 1"), "line 2: wall has zero length");
+        assert_eq!(err("bounds 16\ncover 0 5 1 0 1"), "line 2: cover size must be positive");
+        assert_eq!(err("bounds 16\nzone 0 5 0"), "line 2: zone radius must be positive");
+    }
+
+    #[test]
+    fn validate_catches_layout_problems() {
+        let err = |src: &str| parse(src).err().unwrap_or_default();
+        assert_eq!(err("bounds 4"), "bounds must be between 8 and 64");
+        assert_eq!(err("bounds 16\nspawn 20 0"), "spawn is outside the arena");
+        assert_eq!(err("bounds 16\nspawn 0 0\ncover 0 0 2 1 2"), "cover 1 blocks the player spawn");
+        assert_eq!(err("bounds 16\nzone 15 0 3"), "zone 1 is outside the arena");
+        assert_eq!(err("bounds 16\npickup 0 16"), "pickup 1 is outside the arena");
+        // a box of walls round one corner cuts it off from the spawn
+        assert_eq!(err("bounds 16\nspawn 0 0\nwall 8 8 8 15.6\nwall 8 8 15.6 8"), "part of the floor is walled off from the spawn");
+    }
+}
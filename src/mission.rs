@@ -0,0 +1,135 @@
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::arena::Arena;
+use crate::events::Event;
+use crate::mode::{Mode, Outcome};
+use crate::{zombie_radius, ZKind, Zombie};
+
+// ---- Mission objectives (the Hold / Generator / Scavenge modes) ----
+// Hold       stand in the capture zone until it's taken; zombies head for the zone
+//            and any of them inside it pauses the capture
+// Generator  zombies go for the generator unless you're close; keep it alive
+//            until the power comes back
+// Scavenge   pick up every supply crate, then reach the extraction beacon
+// -------------------------------------------------------------------
+
+const HOLD_TIME: f32 = 45.0;
+const HOLD_RADIUS: f32 = 3.0;
+const GENERATOR_HP: f32 = 20.0;
+const GENERATOR_TIME: f32 = 150.0;
+const GENERATOR_SIZE: Vec3 = vec3(1.6, 1.4, 1.6);
+const AGGRO_RANGE: f32 = 4.0; // zombies this close to the player go for the player instead
+const SCAVENGE_ITEMS: usize = 6;
+const EXTRACT_RADIUS: f32 = 2.0;
+
+#[derive(Clone)]
+pub enum Objective {
+    Hold { pos: Vec2, progress: f32, contested: bool },
+    Generator { pos: Vec2, hp: f32, time_left: f32 },
+    Scavenge { items: Vec<Vec2>, collected: usize, extract: Vec2 },
+}
+
+impl Objective {
+    pub fn new(mode: Mode, arena: &Arena, rng: &RandGenerator) -> Option<Objective> {
+        let spawn = arena.spawn;
+        // open spot roughly `dist` from the player spawn
+        let spot = |dist: f32, r: f32| -> Vec2 {
+            for _ in 0..64 {
+                let a = rng.gen_range(0.0, std::f32::consts::TAU);
+                let p = spawn + vec2(a.cos(), a.sin()) * dist;
+                if arena.is_open(vec3(p.x, 0.5, p.y), r) { return p; }
+            }
+            spawn
+        };
+        match mode {
+            Mode::Hold => Some(Objective::Hold { pos: spot(arena.half * 0.4, HOLD_RADIUS), progress: 0.0, contested: false }),
+            Mode::Generator => Some(Objective::Generator { pos: spot(3.0, GENERATOR_SIZE.x), hp: GENERATOR_HP, time_left: GENERATOR_TIME }),
+            Mode::Scavenge => {
+                let items = (0..SCAVENGE_ITEMS).map(|_| spot(rng.gen_range(arena.half * 0.3, arena.half * 0.85), 0.6)).collect();
+                Some(Objective::Scavenge { items, collected: 0, extract: spot(arena.half * 0.7, EXTRACT_RADIUS) })
+            }
+            _ => None,
+        }
+    }
+
+    // where a zombie at `from` is heading, given the nearest player
+    pub fn zombie_target(&self, from: Vec3, player: Vec3) -> Vec3 {
+        let obj = match self {
+            Objective::Hold { pos, .. } | Objective::Generator { pos, .. } => vec3(pos.x, 0.5, pos.y),
+            Objective::Scavenge { .. } => return player,
+        };
+        if (player - from).length() < AGGRO_RANGE || (player - obj).length() < (obj - from).length() { player } else { obj }
+    }
+
+    // `players`: positions of everyone still standing; any of them can do the job
+    pub fn update(&mut self, dt: f32, players: &[Vec3], zombies: &mut Vec<Zombie>, events: &mut Vec<Event>) -> Option<Outcome> {
+        let near = |at: Vec2, r: f32| players.iter().any(|p| (vec2(p.x, p.z) - at).length() < r);
+        match self {
+            Objective::Hold { pos, progress, contested } => {
+                *contested = zombies.iter().any(|z| z.rise <= 0.0 && (vec2(z.pos.x, z.pos.z) - *pos).length() < HOLD_RADIUS);
+                if near(*pos, HOLD_RADIUS) && !*contested { *progress += dt; }
+                if *progress >= HOLD_TIME { return Some(Outcome::Complete); }
+            }
+            Objective::Generator { pos, hp, time_left } => {
+                // zombies that reach it are spent smashing it
+                let reach = GENERATOR_SIZE.x * 0.5;
+                zombies.retain(|z| {
+                    let hit = z.rise <= 0.0 && (vec2(z.pos.x, z.pos.z) - *pos).length() <= reach + zombie_radius(z.kind);
+                    if hit {
+                        *hp -= match z.kind { ZKind::Tank => 3.0, ZKind::Normal => 2.0, ZKind::Fast => 1.0 };
+                        events.push(Event::GeneratorHit { id: z.id, kind: z.kind, pos: z.pos, hp: *hp });
+                    }
+                    !hit
+                });
+                *time_left -= dt;
+                if *hp <= 0.0 { return Some(Outcome::Failed); }
+                if *time_left <= 0.0 { return Some(Outcome::Complete); }
+            }
+            Objective::Scavenge { items, collected, extract } => {
+                let before = items.len();
+                items.retain(|i| !near(*i, 1.0));
+                *collected += before - items.len();
+                if items.is_empty() && near(*extract, EXTRACT_RADIUS) { return Some(Outcome::Complete); }
+            }
+        }
+        None
+    }
+
+    pub fn hud_line(&self) -> String {
+        match self {
+            Objective::Hold { progress, contested, .. } =>
+                format!("Hold the zone: {:.0}/{:.0}s{}", progress, HOLD_TIME, if *contested { "  — CONTESTED" } else { "" }),
+            Objective::Generator { hp, time_left, .. } =>
+                format!("Generator: {:.0}/{:.0} HP  •  power back in {}", hp.max(0.0), GENERATOR_HP, crate::clock(*time_left)),
+            Objective::Scavenge { items, collected, .. } if items.is_empty() => format!("Supplies {}/{} — get to the extraction beacon!", collected, SCAVENGE_ITEMS),
+            Objective::Scavenge { collected, .. } => format!("Supplies: {}/{}", collected, SCAVENGE_ITEMS),
+        }
+    }
+
+    pub fn draw(&self) {
+        let t = get_time() as f32;
+        match self {
+            Objective::Hold { pos, progress, contested } => {
+                let col = if *contested { Color::new(0.9, 0.2, 0.2, 0.35) } else { Color::new(0.2, 0.6, 1.0, 0.35) };
+                draw_cylinder(vec3(pos.x, 0.0, pos.y), HOLD_RADIUS, HOLD_RADIUS, 0.05, None, col);
+                let fill = HOLD_RADIUS * progress / HOLD_TIME;
+                draw_cylinder(vec3(pos.x, 0.02, pos.y), fill, fill, 0.05, None, Color::new(0.3, 0.9, 1.0, 0.6));
+            }
+            Objective::Generator { pos, hp, .. } => {
+                let c = vec3(pos.x, GENERATOR_SIZE.y * 0.5, pos.y);
+                draw_cube(c, GENERATOR_SIZE, None, Color::from_rgba(200, 170, 40, 255));
+                draw_cube_wires(c, GENERATOR_SIZE, Color::from_rgba(90, 70, 10, 255));
+                // hp bar floating above it
+                let w = 2.0 * (hp / GENERATOR_HP).clamp(0.0, 1.0);
+                draw_cube(vec3(pos.x - 1.0 + w * 0.5, GENERATOR_SIZE.y + 0.6, pos.y), vec3(w, 0.15, 0.15), None, LIME);
+            }
+            Objective::Scavenge { items, extract, .. } => {
+                for i in items { draw_cube(vec3(i.x, 0.4 + 0.15 * (t * 3.0 + i.x).sin(), i.y), vec3(0.6, 0.6, 0.6), None, GOLD); }
+                let open = items.is_empty();
+                let col = if open { Color::new(0.2, 1.0, 0.4, 0.35 + 0.15 * (t * 5.0).sin()) } else { Color::new(0.4, 0.4, 0.4, 0.25) };
+                draw_cylinder(vec3(extract.x, 0.0, extract.y), EXTRACT_RADIUS, EXTRACT_RADIUS, if open { 4.0 } else { 0.05 }, None, col);
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+// ---- Game modes: what drives spawning and how a run ends ----
+// Waves      the classic loop: spawn budget per wave, intermission + heal between
+// Endless    one continuous horde that ramps with time (the 2D version's curve)
+// TimeAttack endless ramp, best score in 5 minutes
+// Hardcore   waves with 1 HP, no Heal drops and no heal between waves
+// Hold / Generator / Scavenge: missions on the endless ramp with an objective (mission.rs)
+// --------------------------------------------------------------
+
+pub const TIME_ATTACK_LIMIT: f32 = 300.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode { Waves, Endless, TimeAttack, Hardcore, Hold, Generator, Scavenge }
+
+pub const MODES: [Mode; 7] = [Mode::Waves, Mode::Endless, Mode::TimeAttack, Mode::Hardcore, Mode::Hold, Mode::Generator, Mode::Scavenge];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome { Died, TimeUp, Complete, Failed }
+
+impl Mode {
+    pub fn name(self) -> &'static str {
+        match self {
+            Mode::Waves => "Waves", Mode::Endless => "Endless", Mode::TimeAttack => "Time Attack", Mode::Hardcore => "Hardcore",
+            Mode::Hold => "Hold the Point", Mode::Generator => "Protect the Generator", Mode::Scavenge => "Scavenge & Extract",
+        }
+    }
+
+    pub fn blurb(self) -> &'static str {
+        match self {
+            Mode::Waves => "Survive wave after wave, healing a little in between",
+            Mode::Endless => "No breaks: the horde keeps growing until you fall",
+            Mode::TimeAttack => "Score as much as you can in 5 minutes",
+            Mode::Hardcore => "Waves with 1 HP, no Heal drops, no healing between waves",
+            Mode::Hold => "Stand in the capture zone until it's yours; zombies in it stall the capture",
+            Mode::Generator => "Zombies go for the generator: keep it running until the power is back",
+            Mode::Scavenge => "Grab every supply crate, then make it to the extraction beacon",
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Mode::Waves => "waves", Mode::Endless => "endless", Mode::TimeAttack => "time_attack", Mode::Hardcore => "hardcore",
+            Mode::Hold => "hold", Mode::Generator => "generator", Mode::Scavenge => "scavenge",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Mode> { MODES.into_iter().find(|m| m.key() == key) }
+
+    pub fn has_waves(self) -> bool { matches!(self, Mode::Waves | Mode::Hardcore) }
+    pub fn max_hp(self, hp: i32) -> i32 { if self == Mode::Hardcore { 1 } else { hp } }
+    pub fn heals(self) -> bool { self != Mode::Hardcore }
+    pub fn time_limit(self) -> Option<f32> { if self == Mode::TimeAttack { Some(TIME_ATTACK_LIMIT) } else { None } }
+
+    pub fn outcome(self, hp: i32, elapsed: f32) -> Option<Outcome> {
+        if hp <= 0 { return Some(Outcome::Died); }
+        match self.time_limit() { Some(limit) if elapsed >= limit => Some(Outcome::TimeUp), _ => None }
+    }
+}
+
+// continuous spawning for modes without waves: seconds between spawns, and the
+// wave number whose zombie mix/speed we use at this point in the run
+pub fn ramp_interval(elapsed: f32) -> f32 { (0.25f32).max(1.2 - elapsed * 0.02) }
+pub fn ramp_wave(elapsed: f32) -> u32 { 1 + (elapsed / 30.0) as u32 }
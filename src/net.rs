@@ -0,0 +1,651 @@
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::arena::Cover;
+use crate::coop::Coop;
+use crate::daily::{Daily, MODIFIERS};
+use crate::difficulty::{Difficulty, PRESETS};
+use crate::input::PlayerInput;
+use crate::map;
+use crate::mission::Objective;
+use crate::mode::{Mode, Outcome};
+use crate::{move_player, Bullet, Drop, Game, Player, Powerup, ZKind, Zombie};
+
+// ---- Online co-op over UDP: the host runs the game, clients send input ----
+// `--host <port>` plays as usual and lets one client in as player 2 of its co-op
+// runs; `--join <addr:port>` connects to a host, which can also be the dedicated
+// server (bin/zombie_shooter_server.rs), where every player is a client. The host
+// is the authority: it runs the one real simulation (step(), same rules as local
+// play), applies each client's inputs to its player and sends a snapshot of the whole run
+// SNAPSHOT_RATE times a second. The client draws other things INTERP_DELAY in
+// the past, blended between the two snapshots around that time, and predicts
+// its own player with the same move_player() the host uses: inputs are applied
+// locally at once, and when a snapshot says which input the host got to, the
+// client restarts from the host's position and replays the newer ones.
+// Packets: "ZS" + version, then a kind byte. Inputs go out every frame with the
+// last few unacknowledged ones repeated, so a lost packet costs nothing.
+// `--lag <ms>` and `--loss <percent>` fake a bad connection on this end, both
+// ways: try `--host 7777` and `--join 127.0.0.1:7777 --lag 80 --loss 5`.
+// Only the simulation is shared: the client gets no events (effects, stats,
+// achievements) and can't pause or restart the host's run. Seats tracks which
+// client plays which player of one run; the game's Host has one, the server one
+// per lobby behind a single socket. `--spectate <addr:port>` joins without a
+// player: spectators get the welcome and snapshots, and their (empty) input
+// packets only keep them from timing out. Times come from now(), not macroquad's
+// clock, since the server has no window.
+// -------------------------------------------------------------------------
+
+const MAGIC: [u8; 3] = *b"ZS\x02";
+const HELLO: u8 = 1;
+const WELCOME: u8 = 2;
+const INPUT: u8 = 3;
+const SNAPSHOT: u8 = 4;
+const BYE: u8 = 5;
+
+const SNAPSHOT_RATE: f64 = 20.0;
+const INTERP_DELAY: f64 = 0.1;
+const TIMEOUT: f64 = 5.0;
+const RESEND: f64 = 0.5; // hello / welcome retry interval
+const MAX_REPEAT: usize = 12; // unacknowledged inputs repeated per packet
+const MAX_PENDING: usize = 256;
+const MAX_INPUT_DT: f32 = 0.1; // the host won't move anyone further than this per input
+const MAX_INPUT_BANK: f32 = 0.25; // ...nor for more than the real time since it last heard from them, plus this
+const MAX_ITEMS: usize = 4096; // per list in a snapshot: more than any run has of anything
+const MAX_PACKET: usize = 65_507;
+const MAX_WATCHERS: usize = 8; // spectators per run
+
+// where the host's run is at, as far as a client cares
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Phase { Playing, Intermission, Over, Paused, Lobby } // Lobby: the server is waiting for players
+
+// ---- byte encoding ----
+
+#[derive(Default)]
+struct Out(Vec<u8>);
+
+impl Out {
+    fn new(kind: u8) -> Out { let mut o = Out(MAGIC.to_vec()); o.u8(kind); o }
+    fn u8(&mut self, v: u8) { self.0.push(v); }
+    fn u16(&mut self, v: usize) { self.0.extend((v.min(u16::MAX as usize) as u16).to_le_bytes()); }
+    fn u32(&mut self, v: u32) { self.0.extend(v.to_le_bytes()); }
+    fn i32(&mut self, v: i32) { self.0.extend(v.to_le_bytes()); }
+    fn u64(&mut self, v: u64) { self.0.extend(v.to_le_bytes()); }
+    fn f32(&mut self, v: f32) { self.0.extend(v.to_le_bytes()); }
+    fn f64(&mut self, v: f64) { self.0.extend(v.to_le_bytes()); }
+    fn vec2(&mut self, v: Vec2) { self.f32(v.x); self.f32(v.y); }
+    fn vec3(&mut self, v: Vec3) { self.f32(v.x); self.f32(v.y); self.f32(v.z); }
+    fn str(&mut self, s: &str) { self.u32(s.len() as u32); self.0.extend(s.as_bytes()); }
+}
+
+struct In<'a>(&'a [u8]);
+
+impl In<'_> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let (head, rest) = self.0.split_at_checked(N)?;
+        self.0 = rest;
+        head.try_into().ok()
+    }
+    fn u8(&mut self) -> Option<u8> { Some(self.take::<1>()?[0]) }
+    fn u16(&mut self) -> Option<usize> { Some(u16::from_le_bytes(self.take()?) as usize) }
+    fn u32(&mut self) -> Option<u32> { Some(u32::from_le_bytes(self.take()?)) }
+    fn i32(&mut self) -> Option<i32> { Some(i32::from_le_bytes(self.take()?)) }
+    fn u64(&mut self) -> Option<u64> { Some(u64::from_le_bytes(self.take()?)) }
+    fn f32(&mut self) -> Option<f32> { Some(f32::from_le_bytes(self.take()?)) }
+    fn f64(&mut self) -> Option<f64> { Some(f64::from_le_bytes(self.take()?)) }
+    fn vec2(&mut self) -> Option<Vec2> { Some(vec2(self.f32()?, self.f32()?)) }
+    fn vec3(&mut self) -> Option<Vec3> { Some(vec3(self.f32()?, self.f32()?, self.f32()?)) }
+    fn str(&mut self) -> Option<String> {
+        let n = self.u32()? as usize;
+        let (s, rest) = self.0.split_at_checked(n)?;
+        self.0 = rest;
+        String::from_utf8(s.to_vec()).ok()
+    }
+    // a u16 count, then that many items; a bogus count fails on the first short read, or at once if over `max`
+    fn list<T>(&mut self, max: usize, mut item: impl FnMut(&mut Self) -> Option<T>) -> Option<Vec<T>> {
+        let n = self.u16()?;
+        if n > max { return None; }
+        (0..n).map(|_| item(self)).collect()
+    }
+}
+
+// kind byte of a packet from this game and protocol version
+fn open(packet: &[u8]) -> Option<(u8, In<'_>)> {
+    let rest = packet.strip_prefix(&MAGIC)?;
+    let (&kind, body) = rest.split_first()?;
+    Some((kind, In(body)))
+}
+
+const KINDS: [ZKind; 3] = [ZKind::Normal, ZKind::Fast, ZKind::Tank];
+const DROPS: [Drop; 4] = [Drop::Heal, Drop::Bomb, Drop::Rapid, Drop::Slow];
+const OUTCOMES: [Outcome; 4] = [Outcome::Died, Outcome::TimeUp, Outcome::Complete, Outcome::Failed];
+const PHASES: [Phase; 5] = [Phase::Playing, Phase::Intermission, Phase::Over, Phase::Paused, Phase::Lobby];
+const COOPS: [Coop; 3] = [Coop::Solo, Coop::Shared, Coop::Separate];
+
+// seconds since the first call
+fn now() -> f64 {
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_secs_f64()
+}
+
+// what a client asks for in its hello
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Join { Play, Watch }
+
+// a hello's request, so whoever has no room can turn it away (and a server knows whether to open a lobby for it)
+pub fn join_request(packet: &[u8]) -> Option<Join> {
+    let (kind, mut i) = open(packet)?;
+    (kind == HELLO).then(|| if i.u8() == Some(1) { Join::Watch } else { Join::Play })
+}
+
+fn index_of<T: PartialEq>(all: &[T], v: &T) -> u8 { all.iter().position(|x| x == v).unwrap_or(0) as u8 }
+fn nth<T: Copy>(all: &[T], i: u8) -> Option<T> { all.get(i as usize).copied() }
+
+fn put_input(o: &mut Out, seq: u32, dt: f32, pin: &PlayerInput) {
+    o.u32(seq); o.f32(dt); o.vec2(pin.move_dir);
+    o.u8(pin.sprint as u8 | (pin.fire as u8) << 1 | (pin.assist as u8) << 2 | (pin.aim.is_some() as u8) << 3);
+    o.vec2(pin.aim.unwrap_or(Vec2::ZERO));
+}
+
+fn get_input(i: &mut In) -> Option<(u32, f32, PlayerInput)> {
+    let (seq, dt, move_dir, flags, aim) = (i.u32()?, i.f32()?, i.vec2()?, i.u8()?, i.vec2()?);
+    if !dt.is_finite() || !move_dir.is_finite() || !aim.is_finite() { return None; }
+    let pin = PlayerInput {
+        move_dir: move_dir.clamp_length_max(1.0), sprint: flags & 1 != 0, fire: flags & 2 != 0, assist: flags & 4 != 0,
+        aim: (flags & 8 != 0).then(|| aim.normalize_or_zero()).filter(|a| *a != Vec2::ZERO),
+    };
+    Some((seq, dt.clamp(0.0, MAX_INPUT_DT), pin))
+}
+
+// ---- the run as sent to clients ----
+
+// `slot` None for a spectator
+fn put_welcome(o: &mut Out, g: &Game, slot: Option<usize>, dedicated: bool) {
+    o.u64(g.seed); o.u8(slot.map_or(u8::MAX, |s| s as u8)); o.u8(dedicated as u8);
+    o.str(g.mode.key()); o.u8(index_of(&COOPS, &g.coop));
+    o.u8(index_of(&PRESETS, &g.diff.preset));
+    for (_, v) in g.diff.fields() { o.f32(v); }
+    o.u64(g.arena.seed.unwrap_or(0)); o.u8(g.arena.seed.is_some() as u8);
+    o.str(&map::to_string(&g.arena));
+    // a daily's modifiers change the rules, so the client's copy needs them too
+    match &g.daily {
+        None => o.u8(0),
+        Some(d) => { o.u8(1); o.str(&d.date); o.u64(d.seed); o.u16(d.mods.len()); for m in &d.mods { o.u8(index_of(&MODIFIERS, m)); } }
+    }
+}
+
+// a client-side copy of the host's run, ready to be filled in by snapshots
+fn get_welcome(i: &mut In) -> Option<(Game, Option<usize>, bool)> {
+    let (seed, slot, dedicated) = (i.u64()?, i.u8()?, i.u8()? != 0);
+    let slot = (slot != u8::MAX).then_some(slot as usize);
+    let mode = Mode::from_key(&i.str()?)?;
+    let coop = nth(&COOPS, i.u8()?)?;
+    let mut diff = Difficulty::preset(nth(&PRESETS, i.u8()?)?);
+    let f = [i.f32()?, i.f32()?, i.f32()?, i.f32()?, i.f32()?, i.f32()?];
+    (diff.player_hp, diff.zombie_speed, diff.zombie_hp, diff.spawn_budget, diff.drop_chance, diff.wave_heal) = (f[0], f[1], f[2], f[3], f[4], f[5]);
+    let (arena_seed, generated) = (i.u64()?, i.u8()? != 0);
+    let mut arena = map::parse(&i.str()?).ok()?;
+    arena.seed = generated.then_some(arena_seed);
+    let mut g = Game::new(arena, mode, diff, coop, seed);
+    if i.u8()? != 0 {
+        let (date, seed) = (i.str()?, i.u64()?);
+        g.daily = Some(Daily { date, seed, mods: i.list(MODIFIERS.len(), |i| nth(&MODIFIERS, i.u8()?))? });
+        g.director.adaptive = false;
+    }
+    slot.is_none_or(|s| s < g.players.len()).then_some((g, slot, dedicated))
+}
+
+struct Snapshot {
+    seed: u64, time: f64, ack: u32, phase: Phase,
+    outcome: Option<Outcome>, score: u32, wave: u32, combo: f32, elapsed: f32, inter_timer: f32,
+    players: Vec<Player>, zombies: Vec<Zombie>, bullets: Vec<Bullet>, drops: Vec<Powerup>, covers: Vec<Cover>,
+    objective: Option<Objective>,
+}
+
+fn put_snapshot(o: &mut Out, g: &Game, time: f64, ack: u32, phase: Phase) {
+    o.u64(g.seed); o.f64(time); o.u32(ack); o.u8(index_of(&PHASES, &phase));
+    o.u8(g.outcome.map_or(0, |x| index_of(&OUTCOMES, &x) + 1));
+    o.u32(g.score); o.u32(g.wave); o.f32(g.combo); o.f32(g.elapsed); o.f32(g.inter_timer);
+    o.u16(g.players.len());
+    for p in &g.players { o.vec3(p.pos); o.i32(p.hp); o.u8(p.downed as u8); o.f32(p.revive); o.u32(p.score); o.f32(p.rapid_left); o.f32(p.slow_left); }
+    o.u16(g.zombies.len());
+    for z in &g.zombies { o.u32(z.id); o.vec3(z.pos); o.u8(index_of(&KINDS, &z.kind)); o.i32(z.hp); o.f32(z.rise); }
+    o.u16(g.bullets.len());
+    for b in &g.bullets { o.vec3(b.pos); o.vec3(b.vel); o.u8(b.owner as u8); }
+    o.u16(g.drops.len());
+    for d in &g.drops { o.vec3(d.pos); o.u8(index_of(&DROPS, &d.kind)); o.f32(d.ttl); }
+    o.u16(g.arena.covers.len());
+    for c in &g.arena.covers { o.vec3(c.pos); o.vec3(c.size); o.f32(c.hp); o.f32(c.max_hp); }
+    match &g.objective {
+        None => o.u8(0),
+        Some(Objective::Hold { pos, progress, contested }) => { o.u8(1); o.vec2(*pos); o.f32(*progress); o.u8(*contested as u8); }
+        Some(Objective::Generator { pos, hp, time_left }) => { o.u8(2); o.vec2(*pos); o.f32(*hp); o.f32(*time_left); }
+        Some(Objective::Scavenge { items, collected, extract }) => {
+            o.u8(3); o.vec2(*extract); o.u32(*collected as u32);
+            o.u16(items.len()); for it in items { o.vec2(*it); }
+        }
+    }
+}
+
+fn get_snapshot(i: &mut In) -> Option<Snapshot> {
+    let (seed, time, ack, phase) = (i.u64()?, i.f64()?, i.u32()?, nth(&PHASES, i.u8()?)?);
+    let outcome = match i.u8()? { 0 => None, n => Some(nth(&OUTCOMES, n - 1)?) };
+    let (score, wave, combo, elapsed, inter_timer) = (i.u32()?, i.u32()?, i.f32()?, i.f32()?, i.f32()?);
+    let players = i.list(MAX_ITEMS, |i| Some(Player {
+        pos: i.vec3()?, hp: i.i32()?, downed: i.u8()? != 0, revive: i.f32()?, score: i.u32()?, rapid_left: i.f32()?, slow_left: i.f32()?, cd: 0.0,
+    }))?;
+    let zombies = i.list(MAX_ITEMS, |i| Some(Zombie { id: i.u32()?, pos: i.vec3()?, kind: nth(&KINDS, i.u8()?)?, hp: i.i32()?, rise: i.f32()?, speed: 0.0 }))?;
+    let bullets = i.list(MAX_ITEMS, |i| Some(Bullet { pos: i.vec3()?, vel: i.vec3()?, owner: i.u8()? as usize, ttl: 1.0 }))?;
+    let drops = i.list(MAX_ITEMS, |i| Some(Powerup { pos: i.vec3()?, kind: nth(&DROPS, i.u8()?)?, ttl: i.f32()? }))?;
+    let covers = i.list(MAX_ITEMS, |i| Some(Cover { pos: i.vec3()?, size: i.vec3()?, hp: i.f32()?, max_hp: i.f32()? }))?;
+    let objective = match i.u8()? {
+        0 => None,
+        1 => Some(Objective::Hold { pos: i.vec2()?, progress: i.f32()?, contested: i.u8()? != 0 }),
+        2 => Some(Objective::Generator { pos: i.vec2()?, hp: i.f32()?, time_left: i.f32()? }),
+        3 => {
+            let (extract, collected) = (i.vec2()?, i.u32()? as usize);
+            Some(Objective::Scavenge { items: i.list(MAX_ITEMS, |i| i.vec2())?, collected, extract })
+        }
+        _ => return None,
+    };
+    Some(Snapshot { seed, time, ack, phase, outcome, score, wave, combo, elapsed, inter_timer, players, zombies, bullets, drops, covers, objective })
+}
+
+// ---- the socket, with optional fake lag and loss ----
+
+pub struct Link {
+    sock: UdpSocket,
+    lag: f64, loss: f32,
+    rng: RandGenerator,
+    outgoing: Vec<(f64, SocketAddr, Vec<u8>)>, // due time, to, bytes
+    incoming: Vec<(f64, SocketAddr, Vec<u8>)>,
+}
+
+impl Link {
+    pub fn bind(addr: &str, lag_ms: f32, loss_percent: f32) -> std::io::Result<Link> {
+        let sock = UdpSocket::bind(addr)?;
+        sock.set_nonblocking(true)?;
+        let rng = RandGenerator::new(); rng.srand(crate::arena::random_seed());
+        Ok(Link { sock, lag: lag_ms.max(0.0) as f64 / 1000.0, loss: loss_percent.clamp(0.0, 100.0) / 100.0, rng, outgoing: vec![], incoming: vec![] })
+    }
+
+    // one-way delay for the next packet: the lag +-10%
+    fn delay(&self) -> f64 { self.lag * self.rng.gen_range(0.9, 1.1) }
+    fn lost(&self) -> bool { self.loss > 0.0 && self.rng.gen_range(0.0, 1.0) < self.loss }
+
+    // turn away a client we have no room for
+    pub fn refuse(&mut self, to: SocketAddr) { self.send(to, Out::new(BYE)); }
+
+    fn send(&mut self, to: SocketAddr, o: Out) {
+        if self.lost() { return; }
+        self.outgoing.push((now() + self.delay(), to, o.0));
+        self.flush();
+    }
+
+    fn flush(&mut self) {
+        let now = now();
+        let sock = &self.sock;
+        self.outgoing.retain(|(due, to, bytes)| {
+            if *due > now { return true; }
+            if let Err(e) = sock.send_to(bytes, to) { eprintln!("net: send to {} failed: {}", to, e); }
+            false
+        });
+    }
+
+    // everything that has "arrived" by now
+    pub fn recv(&mut self) -> Vec<(SocketAddr, Vec<u8>)> {
+        self.flush();
+        let mut buf = vec![0u8; MAX_PACKET];
+        while let Ok((n, from)) = self.sock.recv_from(&mut buf) {
+            if !self.lost() { self.incoming.push((now() + self.delay(), from, buf[..n].to_vec())); }
+        }
+        let now = now();
+        let (ready, later): (Vec<_>, Vec<_>) = std::mem::take(&mut self.incoming).into_iter().partition(|(due, _, _)| *due <= now);
+        self.incoming = later;
+        ready.into_iter().map(|(_, from, bytes)| (from, bytes)).collect()
+    }
+}
+
+// ---- host ----
+
+// a client playing one of the run's players, or just watching
+struct Seat {
+    addr: SocketAddr, slot: Option<usize>, // None: spectator
+    heard: f64, // when they last sent anything
+    applied: u32, // last input sequence number moved for
+    bank: f32, // seconds of movement their inputs may still use up
+    latest: PlayerInput,
+    seed: u64, // the run they think they're in
+    last_welcome: f64,
+}
+
+// the clients in one run and which players they drive. Packets are handed in
+// rather than read here, so a server can put several runs behind one socket
+pub struct Seats {
+    open: Vec<usize>, // player slots clients may take
+    seats: Vec<Seat>,
+    dedicated: bool, // nobody at the host to restart runs; clients word their results screen accordingly
+    next_snapshot: f64,
+}
+
+impl Seats {
+    pub fn new(open: Vec<usize>, dedicated: bool) -> Seats { Seats { open, seats: vec![], dedicated, next_snapshot: 0.0 } }
+
+    fn players(&self) -> impl Iterator<Item = &Seat> { self.seats.iter().filter(|s| s.slot.is_some()) }
+
+    pub fn has(&self, addr: SocketAddr) -> bool { self.seats.iter().any(|s| s.addr == addr) }
+    // players only, from here on; spectators come and go without a say in the run
+    pub fn count(&self) -> usize { self.players().count() }
+    pub fn is_full(&self) -> bool { self.count() >= self.open.len() }
+    pub fn addrs(&self) -> Vec<SocketAddr> { self.players().map(|s| s.addr).collect() }
+    // every client has loaded this run (a new one goes out with a welcome)
+    pub fn in_run(&self, g: &Game) -> bool { self.players().all(|s| s.seed == g.seed) }
+
+    // buttons of whoever plays `slot` this frame, None if nobody does; their movement was already applied in receive()
+    pub fn input(&self, slot: usize) -> Option<PlayerInput> { self.seats.iter().find(|s| s.slot == Some(slot)).map(|s| PlayerInput { move_dir: Vec2::ZERO, ..s.latest }) }
+
+    // one packet; false if it isn't for this run: a stranger that isn't asking to join, or no free seat
+    pub fn receive(&mut self, from: SocketAddr, packet: &[u8], g: &mut Game, phase: Option<Phase>) -> bool {
+        let Some((kind, mut i)) = open(packet) else { return false; };
+        let t = now();
+        let Some(k) = self.seats.iter().position(|s| s.addr == from) else {
+            let slot = match join_request(packet) {
+                None => return false,
+                Some(Join::Watch) if self.seats.len() - self.count() >= MAX_WATCHERS => return false,
+                Some(Join::Watch) => { eprintln!("net: {} is watching", from); None }
+                Some(Join::Play) => {
+                    let Some(slot) = self.open.iter().copied().find(|o| !self.seats.iter().any(|s| s.slot == Some(*o))) else { return false; };
+                    eprintln!("net: {} joined as player {}", from, slot + 1);
+                    Some(slot)
+                }
+            };
+            self.seats.push(Seat { addr: from, slot, heard: t, applied: 0, bank: 0.0, latest: PlayerInput::default(), seed: 0, last_welcome: f64::MIN });
+            return true;
+        };
+        let seat = &mut self.seats[k];
+        // inputs can't add up to more time than has really passed, so sending lots of them doesn't buy speed
+        seat.bank = (seat.bank + (t - seat.heard) as f32).min(MAX_INPUT_BANK);
+        seat.heard = t;
+        match kind {
+            HELLO => { seat.applied = 0; seat.bank = 0.0; seat.seed = 0; seat.latest = PlayerInput::default(); } // rejoining: welcome them again
+            INPUT => {
+                let Some(seed) = i.u64() else { return true; };
+                seat.seed = seed;
+                if seed != g.seed { return true; }
+                let Some(inputs) = i.list(MAX_REPEAT, get_input) else { return true; };
+                // replay anything new, oldest first, with the client's own frame times (as far as the bank goes)
+                for (seq, dt, pin) in inputs.into_iter().rev() {
+                    if seq <= seat.applied { continue; }
+                    let dt = dt.min(seat.bank);
+                    seat.applied = seq; seat.latest = pin; seat.bank -= dt;
+                    let Some(p) = seat.slot.and_then(|i| g.players.get_mut(i)) else { continue; };
+                    if phase == Some(Phase::Playing) && !p.downed { g.stats.distance += move_player(p, &g.arena, &pin, dt); }
+                }
+            }
+            BYE => { eprintln!("net: {} left", from); self.seats.remove(k); }
+            _ => {}
+        }
+        true
+    }
+
+    // once a frame, after the packets: drop anyone gone quiet, then (re)send
+    // welcomes and snapshots. `phase` is None outside a run, which sends nothing
+    pub fn update(&mut self, link: &mut Link, g: &Game, phase: Option<Phase>) {
+        let t = now();
+        self.seats.retain(|s| { let alive = t - s.heard <= TIMEOUT; if !alive { eprintln!("net: {} timed out", s.addr); } alive });
+        let Some(phase) = phase else { return; };
+        let snapshot = t >= self.next_snapshot;
+        if snapshot { self.next_snapshot = t + 1.0 / SNAPSHOT_RATE; }
+        for s in &mut self.seats {
+            if s.slot.is_some_and(|i| i >= g.players.len()) { continue; } // a solo run (e.g. the daily) has no seat for them
+            if s.seed != g.seed && t - s.last_welcome >= RESEND {
+                s.last_welcome = t;
+                s.applied = 0;
+                let mut o = Out::new(WELCOME);
+                put_welcome(&mut o, g, s.slot, self.dedicated);
+                link.send(s.addr, o);
+            }
+            if snapshot {
+                let mut o = Out::new(SNAPSHOT);
+                put_snapshot(&mut o, g, t, s.applied, phase);
+                link.send(s.addr, o);
+            }
+        }
+    }
+}
+
+// the game's side of `--host`: one guest, as player 2
+pub struct Host { link: Link, seats: Seats }
+
+impl Host {
+    pub fn new(link: Link) -> Host { Host { link, seats: Seats::new(vec![1], false) } }
+
+    pub fn connected(&self) -> bool { self.seats.count() > 0 }
+
+    // player 2's buttons this frame, if someone has joined
+    pub fn remote_input(&self) -> Option<PlayerInput> { self.seats.input(1) }
+
+    // once a frame, whatever screen is up; `phase` is None outside a run
+    pub fn frame(&mut self, g: &mut Game, phase: Option<Phase>) {
+        for (from, bytes) in self.link.recv() {
+            // one guest at a time
+            if !self.seats.receive(from, &bytes, g, phase) && join_request(&bytes).is_some() { self.link.refuse(from); }
+        }
+        self.seats.update(&mut self.link, g, phase);
+    }
+}
+
+// ---- client ----
+
+pub struct Client {
+    link: Link,
+    host: SocketAddr,
+    pub game: Option<Game>, // the host's run as last drawn
+    pub slot: Option<usize>, // our player; None while watching
+    pub watching: bool,
+    pub phase: Phase,
+    snaps: Vec<(f64, Snapshot)>, // (local arrival time, snapshot), oldest first
+    pending: Vec<(u32, f32, PlayerInput)>, // inputs the host hasn't confirmed yet
+    seq: u32,
+    predicted: Vec3,
+    heard: f64, last_hello: f64,
+    pub refused: bool, // the host already has a guest
+    pub dedicated: bool, // the host is a server: runs restart on their own
+}
+
+impl Client {
+    pub fn new(link: Link, host: SocketAddr, watching: bool) -> Client {
+        Client { link, host, game: None, slot: None, watching, phase: Phase::Paused, snaps: vec![], pending: vec![], seq: 0, predicted: Vec3::ZERO, heard: now(), last_hello: f64::MIN, refused: false, dedicated: false }
+    }
+
+    pub fn lost(&self) -> bool { now() - self.heard > TIMEOUT }
+
+    // straight out, past any fake lag: there's no next frame to deliver it
+    pub fn leave(&mut self) { let _ = self.link.sock.send_to(&Out::new(BYE).0, self.host); }
+
+    pub fn frame(&mut self, dt: f32, pin: PlayerInput) {
+        let now = now();
+        for (from, bytes) in self.link.recv() {
+            if from != self.host { continue; }
+            let Some((kind, mut i)) = open(&bytes) else { continue; };
+            self.heard = now;
+            match kind {
+                WELCOME => if let Some((g, slot, dedicated)) = get_welcome(&mut i) {
+                    if self.game.as_ref().is_some_and(|old| old.seed == g.seed) { continue; }
+                    self.predicted = slot.map_or(Vec3::ZERO, |s| g.players[s].pos);
+                    self.game = Some(g); self.slot = slot; self.dedicated = dedicated; self.snaps.clear(); self.pending.clear();
+                },
+                SNAPSHOT => if let Some(s) = get_snapshot(&mut i) { self.on_snapshot(now, s); },
+                BYE => self.refused = true,
+                _ => {}
+            }
+        }
+        if self.game.is_none() && now - self.last_hello >= RESEND {
+            self.last_hello = now;
+            let mut o = Out::new(HELLO);
+            o.u8(self.watching as u8);
+            self.link.send(self.host, o);
+        }
+        let Some(g) = self.game.as_mut() else { return; };
+
+        // predict: move at once, remember the input until the host confirms it
+        let me = self.slot.and_then(|s| g.players.get_mut(s));
+        if let Some(me) = me.filter(|me| self.phase == Phase::Playing && !me.downed) {
+            self.seq += 1;
+            me.pos = self.predicted;
+            move_player(me, &g.arena, &pin, dt);
+            self.predicted = me.pos;
+            self.pending.push((self.seq, dt.min(MAX_INPUT_DT), pin));
+            if self.pending.len() > MAX_PENDING { self.pending.remove(0); } // host gone quiet; don't pile up forever
+        }
+        let mut o = Out::new(INPUT);
+        o.u64(g.seed);
+        o.u16(self.pending.len().min(MAX_REPEAT));
+        for (seq, dt, pin) in self.pending.iter().rev().take(MAX_REPEAT) { put_input(&mut o, *seq, *dt, pin); }
+        self.link.send(self.host, o);
+
+        self.interpolate(now);
+    }
+
+    fn on_snapshot(&mut self, now: f64, s: Snapshot) {
+        let Some(g) = self.game.as_mut() else { return; };
+        if s.seed != g.seed || s.players.len() != g.players.len() || self.snaps.last().is_some_and(|(_, l)| l.time >= s.time) { return; }
+        // reconcile: start over from where the host has us and replay what it hasn't seen
+        self.pending.retain(|(seq, _, _)| *seq > s.ack);
+        g.arena.covers = s.covers.clone();
+        if let Some(mut me) = self.slot.map(|i| s.players[i]) {
+            for (_, dt, pin) in &self.pending { move_player(&mut me, &g.arena, pin, *dt); }
+            self.predicted = me.pos;
+        }
+        self.phase = s.phase;
+        self.snaps.push((now, s));
+        if self.snaps.len() > 32 { self.snaps.remove(0); }
+    }
+
+    // fill in the run as the host had it INTERP_DELAY ago; our own player stays predicted
+    fn interpolate(&mut self, now: f64) {
+        let (Some(g), Some((arrived, latest))) = (self.game.as_mut(), self.snaps.last()) else { return; };
+        let t = latest.time + (now - arrived) - INTERP_DELAY;
+        let newer = self.snaps.iter().position(|(_, s)| s.time >= t).unwrap_or(self.snaps.len() - 1);
+        let b = &self.snaps[newer].1;
+        let a = if newer > 0 { &self.snaps[newer - 1].1 } else { b };
+        let alpha = if b.time > a.time { ((t - a.time) / (b.time - a.time)).clamp(0.0, 1.0) as f32 } else { 1.0 };
+
+        g.outcome = b.outcome; g.score = b.score; g.wave = b.wave; g.combo = b.combo; g.elapsed = b.elapsed; g.inter_timer = b.inter_timer;
+        g.players = b.players.iter().zip(&a.players).map(|(pb, pa)| Player { pos: pa.pos.lerp(pb.pos, alpha), ..*pb }).collect();
+        if let Some(i) = self.slot { g.players[i].pos = self.predicted; }
+        g.zombies = b.zombies.iter().map(|z| {
+            let from = a.zombies.iter().find(|o| o.id == z.id).map_or(z.pos, |o| o.pos);
+            Zombie { pos: from.lerp(z.pos, alpha), ..*z }
+        }).collect();
+        let back = (t - b.time) as f32; // bullets fly straight, so just run them back (or on) from b
+        g.bullets = b.bullets.iter().map(|x| Bullet { pos: x.pos + x.vel * back, ..*x }).collect();
+        g.drops = b.drops.clone();
+        g.arena.covers = b.covers.clone();
+        g.objective = b.objective.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena::Arena;
+    use crate::difficulty::Preset;
+
+    fn run() -> Game {
+        let mut g = Game::new(Arena::generate(7), Mode::Generator, Difficulty::preset(Preset::Hard), Coop::Shared, 99);
+        g.score = 1234; g.wave = 3; g.combo = 1.75; g.elapsed = 42.5;
+        g.zombies.push(Zombie { id: 5, pos: vec3(1.0, 0.5, -2.0), speed: 2.4, kind: ZKind::Tank, hp: 3, rise: 0.4 });
+        g.bullets.push(Bullet { pos: vec3(0.0, 1.3, 1.0), vel: vec3(0.0, 0.0, -28.0), ttl: 1.0, owner: 1 });
+        g.drops.push(Powerup { pos: vec3(3.0, 0.5, 3.0), kind: Drop::Slow, ttl: 6.0 });
+        g.players[1].downed = true;
+        g
+    }
+
+    #[test]
+    fn snapshot_round_trips() {
+        let g = run();
+        let mut o = Out::new(SNAPSHOT);
+        put_snapshot(&mut o, &g, 12.5, 77, Phase::Intermission);
+        let (kind, mut i) = open(&o.0).unwrap();
+        assert_eq!(kind, SNAPSHOT);
+        let s = get_snapshot(&mut i).unwrap();
+        assert!(i.0.is_empty());
+        assert_eq!((s.seed, s.time, s.ack), (99, 12.5, 77));
+        assert!(s.phase == Phase::Intermission && s.outcome.is_none());
+        assert_eq!((s.score, s.wave, s.combo, s.elapsed), (1234, 3, 1.75, 42.5));
+        assert_eq!(s.players.len(), 2);
+        assert!(s.players[1].downed && !s.players[0].downed);
+        assert_eq!((s.zombies[0].id, s.zombies[0].pos, s.zombies[0].kind, s.zombies[0].hp, s.zombies[0].rise), (5, vec3(1.0, 0.5, -2.0), ZKind::Tank, 3, 0.4));
+        assert_eq!((s.bullets[0].vel, s.bullets[0].owner), (vec3(0.0, 0.0, -28.0), 1));
+        assert_eq!((s.drops[0].kind, s.drops[0].ttl), (Drop::Slow, 6.0));
+        assert_eq!(s.covers.len(), g.arena.covers.len());
+        assert!(matches!(s.objective, Some(Objective::Generator { .. })));
+    }
+
+    #[test]
+    fn truncated_snapshot_is_rejected() {
+        let mut o = Out::new(SNAPSHOT);
+        put_snapshot(&mut o, &run(), 1.0, 0, Phase::Playing);
+        for cut in [5, 40, o.0.len() - 1] { assert!(get_snapshot(&mut In(&o.0[4..cut])).is_none(), "cut at {}", cut); }
+    }
+
+    #[test]
+    fn welcome_carries_the_rules() {
+        let mut g = Game::daily(Daily::for_day(20_000));
+        g.diff.zombie_hp = 1.5;
+        let mut o = Out::new(WELCOME);
+        put_welcome(&mut o, &g, None, true);
+        let (_, mut i) = open(&o.0).unwrap();
+        let (c, slot, dedicated) = get_welcome(&mut i).unwrap();
+        assert!(slot.is_none() && dedicated);
+        assert_eq!((c.seed, c.mode, c.coop, c.diff.zombie_hp), (g.seed, g.mode, g.coop, 1.5));
+        assert_eq!(map::to_string(&c.arena), map::to_string(&g.arena));
+        let (d, cd) = (g.daily.unwrap(), c.daily.unwrap());
+        assert_eq!((cd.date, cd.seed, cd.mods), (d.date, d.seed, d.mods));
+        assert!(!c.director.adaptive);
+    }
+
+    #[test]
+    fn inputs_are_sanitised() {
+        let mut o = Out::new(INPUT);
+        o.u16(2);
+        put_input(&mut o, 9, 5.0, &PlayerInput { move_dir: vec2(3.0, 4.0), fire: true, aim: Some(vec2(0.0, -2.0)), ..Default::default() });
+        put_input(&mut o, 8, f32::NAN, &PlayerInput::default());
+        let (_, mut i) = open(&o.0).unwrap();
+        assert_eq!(i.u16(), Some(2));
+        let (seq, dt, pin) = get_input(&mut i).unwrap();
+        assert_eq!((seq, dt, pin.move_dir.length(), pin.aim), (9, MAX_INPUT_DT, 1.0, Some(vec2(0.0, -1.0))));
+        assert!(pin.fire && !pin.sprint);
+        assert!(get_input(&mut i).is_none());
+    }
+
+    #[test]
+    fn a_packet_of_inputs_cannot_outrun_the_clock() {
+        let mut g = run();
+        let mut seats = Seats::new(vec![1], false);
+        let from: SocketAddr = "127.0.0.1:9".parse().unwrap();
+        let mut hello = Out::new(HELLO); hello.u8(0);
+        assert!(seats.receive(from, &hello.0, &mut g, Some(Phase::Playing)));
+        let start = g.players[1].pos;
+        // a whole packet of full-length frames, all at once
+        let mut o = Out::new(INPUT);
+        o.u64(g.seed); o.u16(MAX_REPEAT);
+        for n in (1..=MAX_REPEAT as u32).rev() { put_input(&mut o, n, MAX_INPUT_DT, &PlayerInput { move_dir: vec2(1.0, 0.0), ..Default::default() }); }
+        seats.receive(from, &o.0, &mut g, Some(Phase::Playing));
+        assert_eq!(seats.seats[0].applied, MAX_REPEAT as u32);
+        assert!((g.players[1].pos - start).length() < 0.5, "moved {}", (g.players[1].pos - start).length());
+    }
+
+    #[test]
+    fn long_lists_are_refused() {
+        let mut o = Out::new(INPUT);
+        o.u16(MAX_REPEAT + 1);
+        for n in 0..=MAX_REPEAT { put_input(&mut o, n as u32, 0.01, &PlayerInput::default()); }
+        let (_, mut i) = open(&o.0).unwrap();
+        assert!(i.list(MAX_REPEAT, get_input).is_none());
+    }
+}
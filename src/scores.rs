@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use crate::difficulty::{Difficulty, Preset, PRESETS};
+use crate::storage;
+
+// ---- High-score table + run history, kept in the platform data directory ----
+// scores.txt   the best TABLE_SIZE runs for each mode + difficulty, so an Easy
+//              score never pushes a Nightmare one off its table
+// history.txt  every finished run, newest last (capped at HISTORY_SIZE)
+// Both are a version header followed by one tab-separated record per line:
+//   name  score  wave  mode  difficulty  seconds  date
+// A file with an unknown header or unreadable lines is moved aside to
+// <file>.bak (so nothing is lost) and we start fresh, with a warning on screen.
+// ------------------------------------------------------------------------------
+
+const HEADER: &str = "# zombie_shooter scores v1";
+const TABLE_SIZE: usize = 10;
+const HISTORY_SIZE: usize = 500;
+pub const NAME_MAX: usize = 12;
+
+#[derive(Clone)]
+pub struct Record {
+    pub name: String,
+    pub score: u32,
+    pub wave: u32,
+    pub mode: String, // Mode::key()
+    pub diff: String, // Difficulty::key()
+    pub secs: u32,
+    pub date: String,
+}
+
+pub struct Scores {
+    ranked: Vec<Record>, // every table at once, best first
+    pub history: Vec<Record>,
+    pub warnings: Vec<String>,
+}
+
+impl Record {
+    fn parse(line: &str) -> Option<Record> {
+        let f: Vec<&str> = line.split('\t').collect();
+        if f.len() != 7 { return None; }
+        Some(Record {
+            name: f[0].to_owned(), score: f[1].parse().ok()?, wave: f[2].parse().ok()?,
+            mode: f[3].to_owned(), diff: f[4].to_owned(), secs: f[5].parse().ok()?, date: f[6].to_owned(),
+        })
+    }
+
+    fn line(&self) -> String {
+        format!("{}\t{}\t{}\t{}\t{}\t{}\t{}\n", self.name, self.score, self.wave, self.mode, self.diff, self.secs, self.date)
+    }
+}
+
+// tabs/newlines would break the file format; control characters can't be drawn anyway
+pub fn clean_name(name: &str) -> String {
+    let n: String = name.chars().filter(|c| !c.is_control()).take(NAME_MAX).collect();
+    if n.trim().is_empty() { "Player".to_owned() } else { n.trim().to_owned() }
+}
+
+impl Scores {
+    pub fn load() -> Scores {
+        let dir = storage::data_dir();
+        let mut warnings = vec![];
+        let ranked = rank(read(&dir.join("scores.txt"), &mut warnings));
+        let history = read(&dir.join("history.txt"), &mut warnings);
+        Scores { ranked, history, warnings }
+    }
+
+    // one mode + difficulty's table, best first
+    pub fn table(&self, mode: &str, diff: &str) -> Vec<&Record> { self.ranked.iter().filter(|r| r.mode == mode && r.diff == diff).collect() }
+
+    // difficulties worth a table: the presets, then any custom rule sets that have scores
+    pub fn diff_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = PRESETS.iter().filter(|p| **p != Preset::Custom).map(|p| Difficulty::preset(*p).key()).collect();
+        for r in &self.ranked { if !keys.contains(&r.diff) { keys.push(r.diff.clone()); } }
+        keys
+    }
+
+    pub fn qualifies(&self, score: u32, mode: &str, diff: &str) -> bool {
+        let table = self.table(mode, diff);
+        score > 0 && (table.len() < TABLE_SIZE || table.last().is_some_and(|r| score > r.score))
+    }
+
+    // best ranked score for a mode + difficulty; the history also holds co-op totals, which don't compare
+    pub fn best(&self, mode: &str, diff: &str) -> u32 { self.table(mode, diff).first().map_or(0, |r| r.score) }
+
+    // name to offer in the entry box: whoever played last
+    pub fn last_name(&self) -> String { self.history.last().map_or_else(String::new, |r| r.name.clone()) }
+
+    // adds the run to the history, and to its mode + difficulty's table if `ranked`; returns its place there
+    pub fn add(&mut self, rec: Record, ranked: bool) -> Option<usize> {
+        let rank = self.insert(rec, ranked);
+        let dir = storage::data_dir();
+        for (file, recs) in [("scores.txt", &self.ranked), ("history.txt", &self.history)] {
+            let text: String = std::iter::once(format!("{}\n", HEADER)).chain(recs.iter().map(Record::line)).collect();
+            if let Err(e) = storage::write_atomic(&dir.join(file), &text) { self.warnings.push(format!("couldn't save {}: {}", file, e)); }
+        }
+        rank
+    }
+
+    // add() without the saving
+    fn insert(&mut self, rec: Record, ranked: bool) -> Option<usize> {
+        self.history.push(rec.clone());
+        let excess = self.history.len().saturating_sub(HISTORY_SIZE);
+        self.history.drain(..excess);
+        if !ranked || !self.qualifies(rec.score, &rec.mode, &rec.diff) { return None; }
+        let place = self.table(&rec.mode, &rec.diff).iter().filter(|r| r.score >= rec.score).count();
+        let at = self.ranked.iter().position(|r| rec.score > r.score).unwrap_or(self.ranked.len());
+        // whatever falls off the end of that table goes
+        let key = (rec.mode.clone(), rec.diff.clone());
+        self.ranked.insert(at, rec);
+        let mut n = 0;
+        self.ranked.retain(|r| { if (&r.mode, &r.diff) != (&key.0, &key.1) { return true; } n += 1; n <= TABLE_SIZE });
+        Some(place)
+    }
+}
+
+// best first, at most TABLE_SIZE per mode + difficulty
+fn rank(mut recs: Vec<Record>) -> Vec<Record> {
+    recs.sort_by_key(|r| std::cmp::Reverse(r.score));
+    let mut kept: HashMap<(String, String), usize> = HashMap::new();
+    recs.retain(|r| { let n = kept.entry((r.mode.clone(), r.diff.clone())).or_default(); *n += 1; *n <= TABLE_SIZE });
+    recs
+}
+
+fn read(path: &std::path::Path, warnings: &mut Vec<String>) -> Vec<Record> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return vec![],
+        Err(e) => { warnings.push(format!("couldn't read {}: {}", path.display(), e)); return vec![]; }
+    };
+    let mut lines = text.lines();
+    let file = path.file_name().and_then(|f| f.to_str()).unwrap_or("?").to_owned();
+    let problem = match lines.next() {
+        Some(HEADER) => {
+            let recs: Option<Vec<Record>> = lines.filter(|l| !l.trim().is_empty()).map(Record::parse).collect();
+            match recs { Some(r) => return r, None => format!("{} is corrupted", file) }
+        }
+        Some(h) if h.starts_with("# zombie_shooter scores") => format!("{} is from another version ({})", file, h.trim_start_matches("# zombie_shooter scores ")),
+        _ => format!("{} isn't a score file", file),
+    };
+    // keep the old file around rather than overwriting it on the next save
+    let bak = path.with_extension("txt.bak");
+    match std::fs::rename(path, &bak) {
+        Ok(()) => warnings.push(format!("{}; moved it to {} and started fresh", problem, bak.display())),
+        Err(e) => warnings.push(format!("{}; couldn't move it aside: {}", problem, e)),
+    }
+    vec![]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rec(score: u32, mode: &str, diff: &str) -> Record {
+        Record { name: "Ann".to_owned(), score, wave: 3, mode: mode.to_owned(), diff: diff.to_owned(), secs: 95, date: "2026-10-18".to_owned() }
+    }
+
+    fn empty() -> Scores { Scores { ranked: vec![], history: vec![], warnings: vec![] } }
+
+    #[test]
+    fn record_line_round_trips() {
+        let r = rec(1234, "waves", "custom:1.00/0.90/1.00/1.00/1.00/1.00");
+        let back = Record::parse(r.line().trim_end()).unwrap();
+        assert_eq!((back.name, back.score, back.wave, back.mode, back.diff, back.secs, back.date), (r.name, r.score, r.wave, r.mode, r.diff, r.secs, r.date));
+        assert!(Record::parse("Ann\t12\t3\twaves\tnormal\t95").is_none());
+        assert!(Record::parse("Ann\tlots\t3\twaves\tnormal\t95\t2026-10-18").is_none());
+    }
+
+    #[test]
+    fn names_are_cleaned() {
+        assert_eq!(clean_name("  Bob\t\n "), "Bob");
+        assert_eq!(clean_name(" \u{7} "), "Player");
+        assert_eq!(clean_name("abcdefghijklmnop").chars().count(), NAME_MAX);
+    }
+
+    #[test]
+    fn tables_are_kept_per_mode_and_difficulty() {
+        let mut s = empty();
+        for n in 1..=TABLE_SIZE as u32 { s.insert(rec(n * 100, "waves", "normal"), true); }
+        assert!(!s.qualifies(50, "waves", "normal"));
+        assert!(s.qualifies(50, "waves", "hard") && s.qualifies(50, "endless", "normal"));
+        assert!(!s.qualifies(0, "waves", "hard"));
+        // an easy run doesn't push a normal one off
+        assert_eq!(s.insert(rec(5000, "waves", "easy"), true), Some(0));
+        assert_eq!(s.table("waves", "normal").len(), TABLE_SIZE);
+        assert_eq!(s.insert(rec(550, "waves", "normal"), true), Some(5));
+        let normal = s.table("waves", "normal");
+        assert_eq!((normal.len(), normal[0].score, normal[TABLE_SIZE - 1].score), (TABLE_SIZE, 1000, 200));
+        assert_eq!((s.best("waves", "normal"), s.best("waves", "easy"), s.best("waves", "hard")), (1000, 5000, 0));
+    }
+
+    #[test]
+    fn unranked_runs_only_go_in_the_history() {
+        let mut s = empty();
+        assert_eq!(s.insert(rec(9000, "waves", "normal"), false), None); // e.g. a co-op total
+        assert_eq!(s.insert(rec(300, "waves", "normal"), true), Some(0));
+        assert_eq!((s.history.len(), s.best("waves", "normal")), (2, 300));
+    }
+
+    #[test]
+    fn loaded_tables_are_sorted_and_trimmed() {
+        let recs = (0..15).map(|n| rec(n, "waves", "normal")).chain([rec(7, "waves", "hard")]).collect();
+        let ranked = rank(recs);
+        assert_eq!(ranked.len(), TABLE_SIZE + 1);
+        assert_eq!(ranked.iter().filter(|r| r.diff == "normal").map(|r| r.score).collect::<Vec<_>>(), (5..15).rev().collect::<Vec<_>>());
+    }
+}
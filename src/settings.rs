@@ -0,0 +1,133 @@
+use macroquad::prelude::*;
+
+use crate::storage;
+
+// ---- Player settings: <data dir>/settings.txt, one `key value` pair per line ----
+// Read once before the window opens (window_conf in main.rs), edited on the
+// settings screen and saved when leaving it. Resolution, fullscreen, FOV, camera
+// distance, shake and HUD scale apply immediately; vsync and MSAA are window
+// creation options and take effect on the next start. Unknown keys and bad values
+// are ignored (reported on stderr) so an old or hand-edited file still loads.
+// The volume levels are stored for when the game gets sound; nothing plays audio yet.
+// ----------------------------------------------------------------------------------
+
+pub const RESOLUTIONS: [(i32, i32); 5] = [(1024, 576), (1280, 720), (1600, 900), (1920, 1080), (2560, 1440)];
+const MSAA: [i32; 4] = [1, 2, 4, 8];
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Settings {
+    pub width: i32, pub height: i32,
+    pub fullscreen: bool,
+    pub vsync: bool,
+    pub msaa: i32, // samples; 1 = off
+    pub fov: f32, // vertical, degrees
+    pub camera_distance: f32, // multiplier on the default chase offset
+    pub shake: f32, // 0 = off, 1 = full
+    pub master_volume: f32, pub music_volume: f32, pub sfx_volume: f32,
+    pub hud_scale: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            // the old hard-coded `fovy: 45.0` went to glam as radians, which is ~58° — keep that look
+            width: 1280, height: 720, fullscreen: false, vsync: true, msaa: 4, fov: 58.0, camera_distance: 1.0, shake: 1.0,
+            master_volume: 1.0, music_volume: 0.7, sfx_volume: 1.0, hud_scale: 1.0,
+        }
+    }
+}
+
+fn path() -> std::path::PathBuf { storage::data_dir().join("settings.txt") }
+
+impl Settings {
+    pub fn load() -> Settings {
+        let mut s = Settings::default();
+        let Ok(text) = std::fs::read_to_string(path()) else { return s; };
+        for (ln, raw) in text.lines().enumerate() {
+            let line = raw.split('#').next().unwrap_or("").trim();
+            if line.is_empty() { continue; }
+            let f: Vec<&str> = line.split_whitespace().collect();
+            let num = |i: usize| f.get(i).and_then(|v| v.parse::<f32>().ok());
+            let flag = |i: usize| f.get(i).and_then(|v| v.parse::<bool>().ok());
+            let ok = match (f[0], num(1), flag(1)) {
+                ("resolution", Some(w), _) => num(2).map(|h| { s.width = (w as i32).clamp(640, 7680); s.height = (h as i32).clamp(360, 4320); }).is_some(),
+                ("fullscreen", _, Some(b)) => { s.fullscreen = b; true }
+                ("vsync", _, Some(b)) => { s.vsync = b; true }
+                ("msaa", Some(v), _) => { s.msaa = MSAA.into_iter().min_by_key(|m| (m - v as i32).abs()).unwrap_or(1); true }
+                ("fov", Some(v), _) => { s.fov = v.clamp(30.0, 90.0); true }
+                ("camera_distance", Some(v), _) => { s.camera_distance = v.clamp(0.5, 2.0); true }
+                ("shake", Some(v), _) => { s.shake = v.clamp(0.0, 2.0); true }
+                ("master_volume", Some(v), _) => { s.master_volume = v.clamp(0.0, 1.0); true }
+                ("music_volume", Some(v), _) => { s.music_volume = v.clamp(0.0, 1.0); true }
+                ("sfx_volume", Some(v), _) => { s.sfx_volume = v.clamp(0.0, 1.0); true }
+                ("hud_scale", Some(v), _) => { s.hud_scale = v.clamp(0.5, 2.0); true }
+                _ => false,
+            };
+            if !ok { eprintln!("settings.txt line {}: ignoring `{}`", ln + 1, line); }
+        }
+        s
+    }
+
+    pub fn save(&self) {
+        let text = format!(
+            "# zombie_shooter settings\nresolution {} {}\nfullscreen {}\nvsync {}\nmsaa {}\nfov {}\ncamera_distance {}\nshake {}\n\
+             master_volume {}\nmusic_volume {}\nsfx_volume {}\nhud_scale {}\n",
+            self.width, self.height, self.fullscreen, self.vsync, self.msaa, self.fov, self.camera_distance, self.shake,
+            self.master_volume, self.music_volume, self.sfx_volume, self.hud_scale);
+        if let Err(e) = storage::write_atomic(&path(), &text) { eprintln!("couldn't save settings: {}", e); }
+    }
+
+    pub fn window_conf(&self, title: &str) -> Conf {
+        Conf {
+            window_title: title.to_owned(),
+            window_width: self.width, window_height: self.height,
+            fullscreen: self.fullscreen,
+            sample_count: self.msaa,
+            platform: miniquad::conf::Platform { swap_interval: Some(self.vsync as i32), ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    // settings screen rows, in order: label and current value
+    pub fn rows(&self) -> Vec<(&'static str, String)> {
+        let pct = |v: f32| format!("{:.0}%", v * 100.0);
+        let on = |b: bool| (if b { "On" } else { "Off" }).to_owned();
+        vec![
+            ("Resolution", format!("{}x{}", self.width, self.height)),
+            ("Fullscreen", on(self.fullscreen)),
+            ("VSync (restart)", on(self.vsync)),
+            ("MSAA (restart)", if self.msaa <= 1 { "Off".to_owned() } else { format!("{}x", self.msaa) }),
+            ("Field of view", format!("{:.0}°", self.fov)),
+            ("Camera distance", pct(self.camera_distance)),
+            ("Screen shake", pct(self.shake)),
+            ("Master volume", pct(self.master_volume)),
+            ("Music volume", pct(self.music_volume)),
+            ("Effects volume", pct(self.sfx_volume)),
+            ("HUD scale", pct(self.hud_scale)),
+        ]
+    }
+
+    // step row `i` by `dir` (±1) and apply what can be applied right away
+    pub fn adjust(&mut self, i: usize, dir: i32) {
+        let step = |v: f32, by: f32, lo: f32, hi: f32| (((v + by * dir as f32) / by).round() * by).clamp(lo, hi);
+        let cycle = |i: usize, n: usize| (i as i32 + dir).rem_euclid(n as i32) as usize;
+        match i {
+            0 => {
+                let cur = RESOLUTIONS.iter().position(|r| *r == (self.width, self.height)).unwrap_or(1);
+                (self.width, self.height) = RESOLUTIONS[cycle(cur, RESOLUTIONS.len())];
+                if !self.fullscreen { request_new_screen_size(self.width as f32, self.height as f32); }
+            }
+            1 => { self.fullscreen = !self.fullscreen; set_fullscreen(self.fullscreen); }
+            2 => self.vsync = !self.vsync,
+            3 => self.msaa = MSAA[cycle(MSAA.iter().position(|m| *m == self.msaa).unwrap_or(0), MSAA.len())],
+            4 => self.fov = step(self.fov, 5.0, 30.0, 90.0),
+            5 => self.camera_distance = step(self.camera_distance, 0.1, 0.5, 2.0),
+            6 => self.shake = step(self.shake, 0.25, 0.0, 2.0),
+            7 => self.master_volume = step(self.master_volume, 0.1, 0.0, 1.0),
+            8 => self.music_volume = step(self.music_volume, 0.1, 0.0, 1.0),
+            9 => self.sfx_volume = step(self.sfx_volume, 0.1, 0.0, 1.0),
+            10 => self.hud_scale = step(self.hud_scale, 0.25, 0.5, 2.0),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use macroquad::prelude::*;
+
+use crate::coop;
+use crate::{clock, Game};
+
+// ---- Spectating: our own cameras over a run we don't play in ----
+// `--spectate <addr:port>` watches a host's or server's run without taking a seat
+// (net.rs). The cameras only need a Game, so anything that fills one in (a replay
+// player, say) can be watched the same way. C cycles the camera:
+//   free      WASD fly, Space / Ctrl up and down, Shift faster, hold RMB to look
+//   follow    trails one player; Tab picks the next, the wheel zooms
+//   top-down  the whole arena from straight above, flat; WASD pans, the wheel zooms
+// None of it touches the chase camera in render_world. The overlay lists every
+// player's HP and score.
+// -----------------------------------------------
+
+const FLY_SPEED: f32 = 12.0;
+const FLY_FAST: f32 = 3.0; // Shift multiplier
+const LOOK: f32 = 1.6; // radians per screen width of mouse travel
+const FOLLOW_OFFSET: Vec3 = vec3(0.0, 10.0, 12.0);
+const SMOOTHING: f32 = 8.0; // follow camera catch-up rate (1/s)
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Cam { Free, Follow, TopDown }
+
+impl Cam {
+    fn name(self) -> &'static str { match self { Cam::Free => "free camera", Cam::Follow => "following", Cam::TopDown => "top-down" } }
+    fn next(self) -> Cam { match self { Cam::Free => Cam::Follow, Cam::Follow => Cam::TopDown, Cam::TopDown => Cam::Free } }
+}
+
+pub struct Spectator {
+    pub cam: Cam,
+    pos: Vec3, yaw: f32, pitch: f32, // free: yaw 0 looks down -z like the chase camera
+    follow: usize, focus: Option<Vec3>, distance: f32, // follow: player index, smoothed target, zoom
+    pan: Vec2, zoom: f32, // top-down: view centre, fraction of the arena shown
+}
+
+impl Spectator {
+    pub fn new() -> Spectator {
+        Spectator { cam: Cam::Follow, pos: vec3(0.0, 12.0, 16.0), yaw: 0.0, pitch: -0.6, follow: 0, focus: None, distance: 1.0, pan: Vec2::ZERO, zoom: 1.0 }
+    }
+
+    pub fn update(&mut self, g: &Game, dt: f32) {
+        if is_key_pressed(KeyCode::C) {
+            let was = self.camera(g);
+            self.cam = self.cam.next();
+            // the free camera takes off from wherever the last one was looking
+            if self.cam == Cam::Free {
+                let dir = (was.target - was.position).normalize_or_zero();
+                self.pos = was.position;
+                self.yaw = dir.x.atan2(-dir.z);
+                self.pitch = dir.y.clamp(-1.0, 1.0).asin().clamp(-1.5, 1.5);
+            }
+        }
+        if is_key_pressed(KeyCode::Tab) && !g.players.is_empty() { self.follow = (self.follow + 1) % g.players.len(); self.cam = Cam::Follow; }
+        self.follow = self.follow.min(g.players.len().saturating_sub(1));
+        let wheel = mouse_wheel().1;
+        let zoom_step = if wheel > 0.0 { 0.9 } else if wheel < 0.0 { 1.0 / 0.9 } else { 1.0 };
+        let axis = |neg: KeyCode, pos: KeyCode| is_key_down(pos) as i32 as f32 - is_key_down(neg) as i32 as f32;
+        let (side, ahead) = (axis(KeyCode::A, KeyCode::D), axis(KeyCode::S, KeyCode::W));
+
+        match self.cam {
+            Cam::Free => {
+                if is_mouse_button_down(MouseButton::Right) {
+                    let d = mouse_delta_position() * 0.5; // local coords span 2 per screen
+                    self.yaw -= d.x * LOOK;
+                    self.pitch = (self.pitch + d.y * LOOK).clamp(-1.5, 1.5);
+                }
+                let fast = if is_key_down(KeyCode::LeftShift) { FLY_FAST } else { 1.0 };
+                let right = vec3(self.yaw.cos(), 0.0, self.yaw.sin());
+                let up = axis(KeyCode::LeftControl, KeyCode::Space);
+                self.pos += (self.forward() * ahead + right * side + Vec3::Y * up) * FLY_SPEED * fast * dt;
+            }
+            Cam::Follow => {
+                self.distance = (self.distance * zoom_step).clamp(0.3, 4.0);
+                if let Some(p) = g.players.get(self.follow) {
+                    let f = self.focus.unwrap_or(p.pos);
+                    self.focus = Some(f.lerp(p.pos, 1.0 - (-SMOOTHING * dt).exp()));
+                }
+            }
+            Cam::TopDown => {
+                self.zoom = (self.zoom * zoom_step).clamp(0.15, 1.2);
+                let half = g.arena.half;
+                self.pan += vec2(side, -ahead) * half * self.zoom * dt;
+                self.pan = self.pan.clamp(Vec2::splat(-half), Vec2::splat(half));
+            }
+        }
+    }
+
+    fn forward(&self) -> Vec3 { vec3(self.yaw.sin() * self.pitch.cos(), self.pitch.sin(), -self.yaw.cos() * self.pitch.cos()) }
+
+    pub fn camera(&self, g: &Game) -> Camera3D {
+        match self.cam {
+            Cam::Free => Camera3D { position: self.pos, target: self.pos + self.forward(), up: Vec3::Y, ..Default::default() },
+            Cam::Follow => {
+                let focus = self.focus.or_else(|| g.players.get(self.follow).map(|p| p.pos)).unwrap_or(Vec3::ZERO);
+                Camera3D { position: focus + FOLLOW_OFFSET * self.distance, target: focus, up: Vec3::Y, ..Default::default() }
+            }
+            Cam::TopDown => {
+                let span = (g.arena.half * 2.0 + 4.0) * self.zoom;
+                let fit = span.max(span * screen_height() / screen_width()); // fovy is the view's height here
+                let at = vec3(self.pan.x, 0.0, self.pan.y);
+                Camera3D { position: at + vec3(0.0, 80.0, 0.0), target: at, up: vec3(0.0, 0.0, -1.0), fovy: fit, projection: Projection::Orthographics, ..Default::default() }
+            }
+        }
+    }
+
+    // everyone's HP and score, the run's progress, and the camera keys
+    pub fn draw_overlay(&self, g: &Game) {
+        let (x, mut y) = (16.0, 30.0);
+        let progress = match g.mode.time_limit() {
+            Some(limit) => format!("{} left", clock(limit - g.elapsed)),
+            None if g.mode.has_waves() => format!("wave {}", g.wave),
+            None => clock(g.elapsed),
+        };
+        draw_text(&format!("SPECTATING  •  {} / {}  •  {}  •  score {}", g.mode.name(), g.diff.name(), progress, g.score), x, y, 26.0, YELLOW);
+        for (i, p) in g.players.iter().enumerate() {
+            y += 28.0;
+            let col = if g.players.len() > 1 { coop::COLORS[i % coop::COLORS.len()] } else { SKYBLUE };
+            let state = if p.downed { format!("DOWN {:>3.0}%", p.revive / coop::REVIVE_TIME * 100.0) } else { format!("HP {}/{}", p.hp.max(0), g.max_hp) };
+            let marker = if self.cam == Cam::Follow && i == self.follow { ">" } else { " " };
+            draw_text(&format!("{} P{}  {:<10} {} pts", marker, i + 1, state, p.score), x, y, 24.0, col);
+            let w = 120.0 * (p.hp.max(0) as f32 / g.max_hp.max(1) as f32);
+            draw_rectangle(x + 300.0, y - 14.0, 120.0, 10.0, Color::from_rgba(50, 50, 60, 255));
+            draw_rectangle(x + 300.0, y - 14.0, w, 10.0, if p.downed { GRAY } else { col });
+        }
+        if let Some(o) = &g.objective { y += 28.0; draw_text(&o.hud_line(), x, y, 22.0, YELLOW); }
+        let keys = match self.cam {
+            Cam::Free => "WASD fly • Space/Ctrl up/down • Shift fast • hold RMB look",
+            Cam::Follow => "Tab next player • wheel zoom",
+            Cam::TopDown => "WASD pan • wheel zoom",
+        };
+        let info = format!("{}  •  C camera ({})  •  Esc leave", keys, self.cam.name());
+        let t = measure_text(&info, None, 20, 1.0);
+        draw_text(&info, screen_width()*0.5 - t.width*0.5, screen_height() - 18.0, 20.0, GRAY);
+    }
+}
@@ -0,0 +1,122 @@
+use std::io::Write;
+
+use crate::events::{Cause, Event, Listener};
+use crate::scores::Record;
+use crate::storage;
+
+// ---- Per-run statistics, the GameOver stats page and JSON/CSV export ----
+// Everything but distance is collected from the gameplay event stream.
+// Export (X on the results screen) writes <data dir>/stats/run-<date>-<time>.json
+// and appends one row per run to <data dir>/stats/runs.csv, which is the file to
+// point a balance spreadsheet at.
+// --------------------------------------------------------------------------
+
+pub const KIND_NAMES: [&str; 3] = ["normal", "fast", "tank"]; // indexed by ZKind
+pub const DROP_NAMES: [&str; 4] = ["heal", "bomb", "rapid", "slow"]; // indexed by Drop
+
+#[derive(Default)]
+pub struct Stats {
+    pub shots: u32,
+    pub hits: u32,
+    pub kills: [u32; 3],
+    pub bomb_kills: u32, // included in `kills`
+    pub damage: [u32; 3], // hp lost to each zombie type
+    pub powerups: [u32; 4],
+    pub max_combo: f32,
+    pub wave_times: Vec<f32>, // seconds to clear each wave, intermissions excluded
+    wave_start: f32,
+    pub distance: f32,
+}
+
+impl Stats {
+    // time spent on the wave still in progress
+    pub fn current_wave(&self, elapsed: f32) -> f32 { elapsed - self.wave_start }
+
+    pub fn accuracy(&self) -> f32 { if self.shots == 0 { 0.0 } else { self.hits as f32 / self.shots as f32 } }
+
+    pub fn to_json(&self, run: &Record, map: &str) -> String {
+        let obj = |names: &[&str], v: &[u32]| names.iter().zip(v).map(|(n, c)| format!("\"{}\": {}", n, c)).collect::<Vec<_>>().join(", ");
+        let waves = self.wave_times.iter().map(|t| format!("{:.1}", t)).collect::<Vec<_>>().join(", ");
+        format!(
+            "{{\n  \"date\": {},\n  \"name\": {},\n  \"mode\": {},\n  \"difficulty\": {},\n  \"map\": {},\n  \"score\": {},\n  \"wave\": {},\n  \"seconds\": {},\n  \
+             \"shots\": {},\n  \"hits\": {},\n  \"accuracy\": {:.3},\n  \"kills\": {{ {} }},\n  \"bomb_kills\": {},\n  \"damage_taken\": {{ {} }},\n  \
+             \"powerups\": {{ {} }},\n  \"max_combo\": {:.2},\n  \"wave_times\": [{}],\n  \"distance\": {:.1}\n}}\n",
+            json_str(&run.date), json_str(&run.name), json_str(&run.mode), json_str(&run.diff), json_str(map), run.score, run.wave, run.secs,
+            self.shots, self.hits, self.accuracy(), obj(&KIND_NAMES, &self.kills), self.bomb_kills, obj(&KIND_NAMES, &self.damage),
+            obj(&DROP_NAMES, &self.powerups), self.max_combo, waves, self.distance)
+    }
+
+    fn csv_header() -> String {
+        let mut cols: Vec<String> = ["date", "name", "mode", "difficulty", "map", "score", "wave", "seconds", "shots", "hits", "accuracy"].map(String::from).to_vec();
+        cols.extend(KIND_NAMES.iter().map(|k| format!("kills_{}", k)));
+        cols.push("bomb_kills".into());
+        cols.extend(KIND_NAMES.iter().map(|k| format!("damage_{}", k)));
+        cols.extend(DROP_NAMES.iter().map(|d| format!("powerup_{}", d)));
+        cols.extend(["max_combo", "distance", "wave_times"].map(String::from));
+        cols.join(",")
+    }
+
+    fn csv_row(&self, run: &Record, map: &str) -> String {
+        let mut cols = vec![run.date.clone(), csv_field(&run.name), run.mode.clone(), csv_field(&run.diff), csv_field(map),
+            run.score.to_string(), run.wave.to_string(), run.secs.to_string(), self.shots.to_string(), self.hits.to_string(), format!("{:.3}", self.accuracy())];
+        cols.extend(self.kills.iter().map(u32::to_string));
+        cols.push(self.bomb_kills.to_string());
+        cols.extend(self.damage.iter().map(u32::to_string));
+        cols.extend(self.powerups.iter().map(u32::to_string));
+        cols.push(format!("{:.2}", self.max_combo));
+        cols.push(format!("{:.1}", self.distance));
+        cols.push(self.wave_times.iter().map(|t| format!("{:.1}", t)).collect::<Vec<_>>().join(";"));
+        cols.join(",")
+    }
+
+    // returns the JSON file written
+    pub fn export(&self, run: &Record, map: &str) -> std::io::Result<std::path::PathBuf> {
+        let dir = storage::data_dir().join("stats");
+        let stamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.as_secs() % 86_400);
+        let json = dir.join(format!("run-{}-{:05}.json", run.date, stamp));
+        storage::write_atomic(&json, &self.to_json(run, map))?;
+        let csv = dir.join("runs.csv");
+        let fresh = !csv.exists();
+        let mut f = std::fs::OpenOptions::new().create(true).append(true).open(&csv)?;
+        if fresh { writeln!(f, "{}", Stats::csv_header())?; }
+        writeln!(f, "{}", self.csv_row(run, map))?;
+        Ok(json)
+    }
+}
+
+impl Listener for Stats {
+    fn on_event(&mut self, e: &Event) {
+        match *e {
+            Event::ShotFired { .. } => self.shots += 1,
+            Event::ZombieHit { .. } => self.hits += 1,
+            Event::ZombieKilled { kind, cause, combo, .. } => {
+                self.kills[kind as usize] += 1;
+                if cause == Cause::Bomb { self.bomb_kills += 1; }
+                self.max_combo = self.max_combo.max(combo);
+            }
+            Event::PlayerDamaged { source, amount, .. } => self.damage[source as usize] += amount.max(0) as u32,
+            Event::PowerupPicked { kind, .. } => self.powerups[kind as usize] += 1,
+            Event::WaveStarted { at, .. } => self.wave_start = at,
+            Event::WaveCleared { at, .. } => self.wave_times.push(at - self.wave_start),
+            Event::ZombieSpawned { .. } | Event::GeneratorHit { .. } | Event::PlayerDowned { .. } | Event::PlayerRevived { .. } | Event::GameOver { .. } => {}
+        }
+    }
+}
+
+fn json_str(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) { format!("\"{}\"", s.replace('"', "\"\"")) } else { s.to_owned() }
+}
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+// ---- Where persistent files live: the platform's per-user data directory ----
+// Linux    $XDG_DATA_HOME/zombie_shooter  (default ~/.local/share/zombie_shooter)
+// macOS    ~/Library/Application Support/zombie_shooter
+// Windows  %APPDATA%\zombie_shooter
+// Falls back to ./data when none of those can be found.
+// Also: crash-safe writes, and the UTC calendar date used to stamp saved records.
+// ----------------------------------------------------------------------------
+
+const APP_DIR: &str = "zombie_shooter";
+
+pub fn data_dir() -> PathBuf {
+    let env = |k: &str| std::env::var_os(k).filter(|v| !v.is_empty()).map(PathBuf::from);
+    let base = if cfg!(windows) {
+        env("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env("HOME").map(|h| h.join("Library/Application Support"))
+    } else {
+        env("XDG_DATA_HOME").or_else(|| env("HOME").map(|h| h.join(".local/share")))
+    };
+    base.map(|b| b.join(APP_DIR)).unwrap_or_else(|| PathBuf::from("data"))
+}
+
+// write via a temp file + rename so a crash mid-save can't leave a half-written file
+pub fn write_atomic(path: &std::path::Path, text: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() { std::fs::create_dir_all(dir)?; }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, text)?;
+    std::fs::rename(&tmp, path)
+}
+
+// whole days since 1970-01-01 (UTC)
+pub fn days_today() -> i64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| (d.as_secs() / 86_400) as i64).unwrap_or(0)
+}
+
+// days since 1970-01-01 -> "YYYY-MM-DD" (proleptic Gregorian, Howard Hinnant's algorithm)
+pub fn civil_date(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_dates() {
+        assert_eq!(civil_date(0), "1970-01-01");
+        assert_eq!(civil_date(-1), "1969-12-31");
+        assert_eq!(civil_date(11_016), "2000-02-29");
+        assert_eq!(civil_date(19_782), "2024-02-29");
+        assert_eq!(civil_date(20_744), "2026-10-18");
+        assert_eq!(civil_date(-25_567), "1900-01-01");
+    }
+}
@@ -0,0 +1,117 @@
+use macroquad::prelude::*;
+
+use crate::gamepad::{self, Button};
+
+// ---- Menu widgets: vertical lists navigable by keyboard or mouse ----
+// W/S or ↑/↓ move, Enter/Space activate, A/D or ←/→ change a row's value, Esc
+// goes back. The mouse selects by hovering, activates by clicking, and clicking
+// the < / > of a valued row adjusts it. On a pad the d-pad moves and adjusts, A
+// activates and B goes back. Everything is immediate mode: call the
+// widget each frame and act on what it returns.
+// ---------------------------------------------------------------------
+
+const ROW_H: f32 = 44.0;
+const ROW_W: f32 = 420.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Action { None, Activate(usize), Adjust(usize, i32), Back }
+
+// a menu row; adjustable rows (toggles, sliders, choices) get < > arrows, info rows
+// just show a value on the right and activate like buttons
+pub struct Row { pub label: String, pub value: Option<String>, pub adjustable: bool }
+
+impl Row {
+    pub fn button(label: &str) -> Row { Row { label: label.to_owned(), value: None, adjustable: false } }
+    pub fn value(label: &str, value: String) -> Row { Row { label: label.to_owned(), value: Some(value), adjustable: true } }
+    pub fn info(label: &str, value: String) -> Row { Row { label: label.to_owned(), value: Some(value), adjustable: false } }
+}
+
+pub fn title(text: &str, y: f32) {
+    let tw = measure_text(text, None, 48, 1.0);
+    draw_text(text, screen_width()*0.5 - tw.width*0.5, y, 48.0, YELLOW);
+}
+
+pub fn hint(text: &str) {
+    let t = measure_text(text, None, 20, 1.0);
+    draw_text(text, screen_width()*0.5 - t.width*0.5, screen_height() - 18.0, 20.0, GRAY);
+}
+
+// darken whatever is behind a pause menu or dialog
+pub fn dim() { draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.6)); }
+
+pub fn menu(rows: &[Row], sel: &mut usize, top: f32) -> Action {
+    if rows.is_empty() { return Action::None; }
+    let n = rows.len();
+    *sel = (*sel).min(n - 1);
+    let x = screen_width()*0.5 - ROW_W*0.5;
+    let (mx, my) = mouse_position();
+    let moved = mouse_delta_position() != Vec2::ZERO;
+    let mut action = Action::None;
+
+    for (i, r) in rows.iter().enumerate() {
+        let y = top + i as f32 * ROW_H;
+        let hover = mx >= x && mx <= x + ROW_W && my >= y && my < y + ROW_H - 6.0;
+        if hover && moved { *sel = i; }
+        let active = i == *sel;
+        draw_rectangle(x, y, ROW_W, ROW_H - 6.0, if active { Color::from_rgba(50,56,80,255) } else { Color::from_rgba(26,28,38,255) });
+        if active { draw_rectangle_lines(x, y, ROW_W, ROW_H - 6.0, 2.0, YELLOW); }
+        let col = if active { WHITE } else { LIGHTGRAY };
+        draw_text(&r.label, x + 16.0, y + 26.0, 26.0, col);
+        if let Some(v) = &r.value {
+            let text = if r.adjustable { format!("<  {}  >", v) } else { v.clone() };
+            let tw = measure_text(&text, None, 24, 1.0).width;
+            draw_text(&text, x + ROW_W - tw - 16.0, y + 26.0, 24.0, if active { YELLOW } else { GRAY });
+        }
+        if hover && is_mouse_button_pressed(MouseButton::Left) {
+            *sel = i;
+            action = match r.adjustable {
+                // left third of the value area steps down, everything else steps up
+                true if mx < x + ROW_W * 0.66 && mx > x + ROW_W * 0.45 => Action::Adjust(i, -1),
+                true => Action::Adjust(i, 1),
+                false => Action::Activate(i),
+            };
+        }
+    }
+
+    let pad = gamepad::is_pressed;
+    if is_key_pressed(KeyCode::Down) || is_key_pressed(KeyCode::S) || pad(Button::Down) { *sel = (*sel + 1) % n; }
+    if is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::W) || pad(Button::Up) { *sel = (*sel + n - 1) % n; }
+    let valued = rows[*sel].adjustable;
+    if valued && (is_key_pressed(KeyCode::Left) || is_key_pressed(KeyCode::A) || pad(Button::Left)) { action = Action::Adjust(*sel, -1); }
+    if valued && (is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::D) || pad(Button::Right)) { action = Action::Adjust(*sel, 1); }
+    if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Space) || pad(Button::A) {
+        action = if valued { Action::Adjust(*sel, 1) } else { Action::Activate(*sel) };
+    }
+    if is_key_pressed(KeyCode::Escape) || pad(Button::B) { action = Action::Back; }
+    action
+}
+
+// yes/no box over whatever is on screen; Some(answer) once decided
+pub fn confirm(question: &str, sel: &mut usize) -> Option<bool> {
+    dim();
+    let (w, h) = (520.0, 190.0);
+    let (x, y) = (screen_width()*0.5 - w*0.5, screen_height()*0.5 - h*0.5);
+    draw_rectangle(x, y, w, h, Color::from_rgba(20,22,30,255));
+    draw_rectangle_lines(x, y, w, h, 2.0, GRAY);
+    let tw = measure_text(question, None, 30, 1.0);
+    draw_text(question, screen_width()*0.5 - tw.width*0.5, y + 48.0, 30.0, WHITE);
+    match menu(&[Row::button("Yes"), Row::button("No")], sel, y + 72.0) {
+        Action::Activate(i) => Some(i == 0),
+        Action::Back => Some(false),
+        _ => None,
+    }
+}
+
+// centred box with a couple of lines over a dimmed screen (prompts that wait for a key)
+pub fn notice(lines: &[&str]) {
+    dim();
+    let (w, h) = (640.0, 50.0 + 34.0 * lines.len() as f32);
+    let (x, y) = (screen_width()*0.5 - w*0.5, screen_height()*0.5 - h*0.5);
+    draw_rectangle(x, y, w, h, Color::from_rgba(20,22,30,255));
+    draw_rectangle_lines(x, y, w, h, 2.0, GRAY);
+    for (i, l) in lines.iter().enumerate() {
+        let (size, col) = if i == 0 { (28u16, WHITE) } else { (22u16, LIGHTGRAY) };
+        let tw = measure_text(l, None, size, 1.0);
+        draw_text(l, screen_width()*0.5 - tw.width*0.5, y + 42.0 + 34.0 * i as f32, size as f32, col);
+    }
+}